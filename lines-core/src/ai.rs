@@ -0,0 +1,253 @@
+use crate::{best_move, Board, Coordinates, Game, GameRng};
+
+// value of every cleared ball, higher than any board position
+const CLEAR_VALUE: i64 = 1000;
+// value of every free tile of the board
+const FREE_TILE_VALUE: i64 = 2;
+// value of the position without free tiles
+const GAME_OVER_VALUE: i64 = -100_000;
+// segments with more balls aren't valued higher, so long lines of custom rules don't overflow
+const MAX_SEGMENT_BALLS: u32 = 16;
+
+/// Player which chooses the moves of the game
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+
+    /// Move of the ball to the empty tile, `None` if no ball can be moved
+    fn choose_move(&mut self, game: &Game) -> Option<(Coordinates, Coordinates)>;
+}
+
+/// Moves a random ball to a random reachable tile, the baseline for other strategies
+#[derive(Debug, Clone)]
+pub struct Random {
+    rng: GameRng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: GameRng::new(seed),
+        }
+    }
+}
+
+impl Strategy for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn choose_move(&mut self, game: &Game) -> Option<(Coordinates, Coordinates)> {
+        let board = game.board();
+        let moves: Vec<_> = board
+            .balls()
+            .flat_map(|(from, _)| {
+                board
+                    .reachable_tiles(&from)
+                    .into_iter()
+                    .map(move |to| (from, to))
+            })
+            .collect();
+        self.rng.choose(&moves).copied()
+    }
+}
+
+/// Moves the ball which clears the most balls or grows the longest line right now
+#[derive(Debug, Clone, Default)]
+pub struct Greedy;
+
+impl Strategy for Greedy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn choose_move(&mut self, game: &Game) -> Option<(Coordinates, Coordinates)> {
+        best_move(game.board()).map(|hint| (hint.from, hint.to))
+    }
+}
+
+/// Greedy line building with a look-ahead: the best moves are checked against
+/// the balls of the next colors spawned on random free tiles
+#[derive(Debug, Clone)]
+pub struct Ai {
+    rng: GameRng,
+    // count of the best greedy moves checked by the look-ahead
+    candidates: usize,
+    // count of random spawns of the next balls for every checked move
+    samples: usize,
+}
+
+impl Default for Ai {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Ai {
+    // the same seed gives the same moves in the same game
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: GameRng::new(seed),
+            candidates: 16,
+            samples: 4,
+        }
+    }
+
+    // value of the position after the move and the spawn of the next balls
+    fn look_ahead(&mut self, game: &Game, from: &Coordinates, to: &Coordinates) -> i64 {
+        let (board, cleared) = make_move(game.board(), from, to);
+        if cleared > 0 {
+            // no balls are spawned after the cleared line
+            return position_value(&board, cleared);
+        }
+
+        let mut total: i64 = 0;
+        for _ in 0..self.samples {
+            let mut board = board.clone();
+            for color in game.next_balls() {
+                let Some(coord) = board.get_free_tile(&mut self.rng) else {
+                    break;
+                };
+                board.set(coord, Some(*color));
+            }
+            let cleared = clear_lines(&mut board);
+            total = total.saturating_add(match board.free_tiles().is_empty() {
+                true => GAME_OVER_VALUE,
+                false => position_value(&board, cleared),
+            });
+        }
+        total / self.samples.max(1) as i64
+    }
+}
+
+impl Strategy for Ai {
+    fn name(&self) -> &'static str {
+        "ai"
+    }
+
+    fn choose_move(&mut self, game: &Game) -> Option<(Coordinates, Coordinates)> {
+        let board = game.board();
+        let balls: Vec<_> = board.balls().collect();
+
+        let mut moves: Vec<_> = balls
+            .iter()
+            .flat_map(|(from, _)| {
+                board.reachable_tiles(from).into_iter().map(|to| {
+                    let (after, cleared) = make_move(board, from, &to);
+                    (position_value(&after, cleared), *from, to)
+                })
+            })
+            .collect();
+        moves.sort_by_key(|(value, ..)| -value);
+        moves.truncate(self.candidates);
+
+        moves
+            .into_iter()
+            .map(|(_, from, to)| (self.look_ahead(game, &from, &to), from, to))
+            .max_by_key(|(value, ..)| *value)
+            .map(|(_, from, to)| (from, to))
+    }
+}
+
+/// Play the game with the strategy until it's over or the strategy gives up,
+/// returns count of the made moves
+pub fn autoplay(game: &mut Game, strategy: &mut dyn Strategy) -> usize {
+    let mut moves = 0;
+    while !game.is_over() {
+        let Some((from, to)) = strategy.choose_move(game) else {
+            break;
+        };
+        if game.make_move(from, to).is_err() {
+            break;
+        }
+        moves += 1;
+    }
+    moves
+}
+
+// value of the board after the move which cleared the balls
+fn position_value(board: &Board, cleared: usize) -> i64 {
+    (CLEAR_VALUE * cleared as i64).saturating_add(board_value(board))
+}
+
+// every segment of the line length with balls of a single color is a line to complete,
+// the more balls it has the better. Free space is good for the next moves
+fn board_value(board: &Board) -> i64 {
+    let length = board.line_length() as i32;
+    let (width, height) = (board.width() as i32, board.height() as i32);
+    let mut value = FREE_TILE_VALUE * board.free_tiles().len() as i64;
+
+    for (dx, dy) in [(1, 0), (0, 1), (1, 1), (1, -1)] {
+        for (x, y) in (0..width).flat_map(|x| (0..height).map(move |y| (x, y))) {
+            let (end_x, end_y) = (x + dx * (length - 1), y + dy * (length - 1));
+            if end_x >= width || end_y < 0 || end_y >= height {
+                continue;
+            }
+
+            let mut color = None;
+            let mut count: u32 = 0;
+            let single_color = (0..length).all(|i| {
+                let coord = Coordinates((x + dx * i) as u8, (y + dy * i) as u8);
+                match (board.get(&coord), color) {
+                    (None, _) => true,
+                    (Some(ball), None) => {
+                        color = Some(ball);
+                        count += 1;
+                        true
+                    }
+                    (Some(ball), Some(other)) => {
+                        count += 1;
+                        ball == other
+                    }
+                }
+            });
+            if single_color {
+                value = value.saturating_add(1 << (2 * count.min(MAX_SEGMENT_BALLS)));
+            }
+        }
+    }
+
+    value
+}
+
+// board after the move of the ball and count of the cleared balls
+fn make_move(board: &Board, from: &Coordinates, to: &Coordinates) -> (Board, usize) {
+    let mut board = board.clone();
+    let color = board.set(*from, None);
+    board.set(*to, color);
+    let cleared = clear_lines(&mut board);
+    (board, cleared)
+}
+
+// remove lines from the board, returns count of the removed balls
+fn clear_lines(board: &mut Board) -> usize {
+    let shapes = board.get_cleared_shapes();
+    for coord in shapes.iter().flat_map(|shape| &shape.balls) {
+        board.set(*coord, None);
+    }
+    shapes.iter().map(|shape| shape.balls.len()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BallColor, GameConfig};
+
+    #[test]
+    fn long_lines_dont_overflow_the_value() {
+        let config = GameConfig {
+            width: 20,
+            height: 20,
+            line_length: 20,
+            ..GameConfig::default()
+        };
+        let mut board = Board::new(&config);
+        for x in 0..19 {
+            board.set(Coordinates(x, 0), Some(BallColor::Red));
+        }
+        assert!(board_value(&board) > 1 << (2 * MAX_SEGMENT_BALLS));
+
+        let mut game = Game::with_config(config, 1);
+        game.start();
+        assert!(Ai::new(1).choose_move(&game).is_some());
+    }
+}
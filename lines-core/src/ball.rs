@@ -0,0 +1,39 @@
+use crate::GameRng;
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::component::Component;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BallColor {
+    Red,
+    Blue,
+    Cyan,
+    Green,
+    Purple,
+    Brown,
+    Yellow,
+    Orange,
+}
+
+impl BallColor {
+    pub const ALL: [BallColor; 8] = [
+        BallColor::Red,
+        BallColor::Blue,
+        BallColor::Cyan,
+        BallColor::Green,
+        BallColor::Purple,
+        BallColor::Brown,
+        BallColor::Yellow,
+        BallColor::Orange,
+    ];
+
+    // random color from the first `colors` colors
+    pub fn random(rng: &mut GameRng, colors: u8) -> Self {
+        let colors = (colors as usize).clamp(1, Self::ALL.len());
+        *rng.choose(&Self::ALL[..colors]).unwrap()
+    }
+}
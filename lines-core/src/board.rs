@@ -0,0 +1,514 @@
+use std::collections::{HashSet, VecDeque};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{BallColor, ClearedShape, Coordinates, GameConfig, GameRng};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Board {
+    width: u8,
+    height: u8,
+    line_length: usize,
+    tiles: Vec<Option<BallColor>>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(&GameConfig::default())
+    }
+}
+
+impl Board {
+    pub fn new(config: &GameConfig) -> Self {
+        Self {
+            width: config.width,
+            height: config.height,
+            line_length: config.line_length,
+            tiles: vec![None; config.tile_count()],
+        }
+    }
+
+    // board has the size and the line length of the config, false for broken saved boards
+    pub fn matches(&self, config: &GameConfig) -> bool {
+        self.width == config.width
+            && self.height == config.height
+            && self.line_length == config.line_length
+            && self.tiles.len() == config.tile_count()
+    }
+
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    // minimal count of balls in a row to clear a line
+    pub fn line_length(&self) -> usize {
+        self.line_length
+    }
+
+    fn index(&self, coordinates: &Coordinates) -> Option<usize> {
+        let Coordinates(x, y) = *coordinates;
+        if x < self.width && y < self.height {
+            Some(x as usize * self.height as usize + y as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, coordinates: &Coordinates) -> bool {
+        self.index(coordinates).is_some()
+    }
+
+    // ball on the tile, `None` for empty tiles and tiles out of the board
+    pub fn get(&self, coordinates: &Coordinates) -> Option<BallColor> {
+        self.index(coordinates).and_then(|index| self.tiles[index])
+    }
+
+    // put ball on the tile and return the previous one
+    pub fn set(&mut self, coordinates: Coordinates, ball: Option<BallColor>) -> Option<BallColor> {
+        let index = self.index(&coordinates).expect("Coordinates out of board");
+        std::mem::replace(&mut self.tiles[index], ball)
+    }
+
+    pub fn clear(&mut self) {
+        self.tiles.iter_mut().for_each(|tile| *tile = None);
+    }
+
+    // all coordinates of the board, sorted by column and row
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> {
+        let height = self.height;
+        (0..self.width).flat_map(move |x| (0..height).map(move |y| Coordinates(x, y)))
+    }
+
+    pub fn balls(&self) -> impl Iterator<Item = (Coordinates, BallColor)> + '_ {
+        self.coordinates()
+            .filter_map(|coord| self.get(&coord).map(|color| (coord, color)))
+    }
+
+    pub fn free_tiles(&self) -> Vec<Coordinates> {
+        self.coordinates()
+            .filter(|coord| self.get(coord).is_none())
+            .collect()
+    }
+
+    pub fn get_free_tile(&self, rng: &mut GameRng) -> Option<Coordinates> {
+        rng.choose(&self.free_tiles()).copied()
+    }
+
+    // get all lines for board. horizontal, vertical and diagonal
+    fn get_lines(&self) -> Vec<Vec<Coordinates>> {
+        let mut lines: Vec<_> = vec![];
+        let width = self.width as i32;
+        let height = self.height as i32;
+
+        // start tiles of lines and their directions
+        let mut starts = vec![];
+        for x in 0..width {
+            // vertical and diagonal lines from the top row
+            starts.push((x, 0, 0, 1));
+            starts.push((x, 0, 1, 1));
+            starts.push((x, 0, -1, 1));
+        }
+        for y in 0..height {
+            // horizontal lines from the left column
+            starts.push((0, y, 1, 0));
+            if y > 0 {
+                // diagonal lines from the side columns
+                starts.push((0, y, 1, 1));
+                starts.push((width - 1, y, -1, 1));
+            }
+        }
+
+        for (mut x, mut y, dx, dy) in starts {
+            let mut line = vec![];
+
+            while x >= 0 && x < width && y >= 0 && y < height {
+                line.push(Coordinates(x as u8, y as u8));
+                x += dx;
+                y += dy;
+            }
+
+            if line.len() >= self.line_length {
+                lines.push(line);
+            }
+        }
+
+        lines
+    }
+
+    pub fn get_balls_for_despawn(&self) -> Vec<Vec<Coordinates>> {
+        let lines = self.get_lines();
+        let mut result = vec![];
+        let mut acc = vec![];
+
+        for line in lines {
+            let mut last_color = None;
+            acc.clear();
+
+            for coord in line {
+                match self.get(&coord) {
+                    Some(color) => {
+                        if last_color.is_some() && last_color != Some(color) {
+                            last_color = None;
+                            if acc.len() >= self.line_length {
+                                result.push(acc.clone());
+                            }
+                            acc.clear();
+                        }
+                        if last_color.is_none() || last_color == Some(color) {
+                            last_color = Some(color);
+                            acc.push(coord);
+                        }
+                    }
+                    // clear if tile is empty
+                    None => {
+                        last_color = None;
+                        if acc.len() >= self.line_length {
+                            result.push(acc.clone());
+                        }
+                        acc.clear();
+                    }
+                }
+            }
+
+            // clear on new line
+            if acc.len() >= self.line_length {
+                result.push(acc.clone());
+            }
+        }
+
+        result
+    }
+
+    // lines to clear grouped by shared balls, every ball is cleared once
+    pub fn get_cleared_shapes(&self) -> Vec<ClearedShape> {
+        ClearedShape::from_lines(self.get_balls_for_despawn())
+    }
+
+    fn get_neighbors(&self, coordinates: &Coordinates) -> Vec<Coordinates> {
+        let mut neighbors = Vec::new();
+
+        let row = coordinates.0;
+        let col = coordinates.1;
+
+        if row > 0 {
+            neighbors.push(Coordinates(row - 1, col));
+        }
+        if row < self.width - 1 {
+            neighbors.push(Coordinates(row + 1, col));
+        }
+        if col > 0 {
+            neighbors.push(Coordinates(row, col - 1));
+        }
+        if col < self.height - 1 {
+            neighbors.push(Coordinates(row, col + 1));
+        }
+
+        neighbors
+    }
+
+    pub fn get_path_to_move(
+        &self,
+        from: &Coordinates,
+        to: &Coordinates,
+    ) -> Option<Vec<Coordinates>> {
+        let mut visited = HashSet::new();
+        let mut prev = vec![vec![Coordinates(0, 0); self.height as usize]; self.width as usize];
+        let mut queue = VecDeque::new();
+
+        visited.insert(*from);
+        queue.push_back(*from);
+
+        while let Some(coord) = queue.pop_front() {
+            if coord == *to {
+                let mut path = Vec::new();
+                let mut current = *to;
+
+                while current != *from {
+                    path.push(current);
+                    current = prev[current.0 as usize][current.1 as usize];
+                }
+
+                path.push(*from);
+                path.reverse();
+
+                return Some(path);
+            }
+
+            let neighbors = self.get_neighbors(&coord);
+
+            for next_coord in neighbors {
+                if self.get(&next_coord).is_none() && !visited.contains(&next_coord) {
+                    visited.insert(next_coord);
+                    prev[next_coord.0 as usize][next_coord.1 as usize] = coord;
+                    queue.push_back(next_coord);
+                }
+            }
+        }
+
+        None
+    }
+
+    // empty tiles the ball can be moved to
+    pub fn reachable_tiles(&self, from: &Coordinates) -> Vec<Coordinates> {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([*from]);
+        let mut tiles = Vec::new();
+
+        visited.insert(*from);
+        while let Some(coord) = queue.pop_front() {
+            for next_coord in self.get_neighbors(&coord) {
+                if self.get(&next_coord).is_none() && visited.insert(next_coord) {
+                    tiles.push(next_coord);
+                    queue.push_back(next_coord);
+                }
+            }
+        }
+
+        tiles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use BallColor::*;
+
+    fn board(width: u8, height: u8, line_length: usize) -> Board {
+        Board::new(&GameConfig {
+            width,
+            height,
+            line_length,
+            ..GameConfig::default()
+        })
+    }
+
+    fn put(board: &mut Board, color: BallColor, coords: impl IntoIterator<Item = (u8, u8)>) {
+        for (x, y) in coords {
+            board.set(Coordinates(x, y), Some(color));
+        }
+    }
+
+    fn lengths(lines: &[Vec<Coordinates>]) -> Vec<usize> {
+        let mut lengths: Vec<_> = lines.iter().map(Vec::len).collect();
+        lengths.sort();
+        lengths
+    }
+
+    #[test]
+    fn get_lines_counts_rows_columns_and_long_diagonals() {
+        // 9 rows, 9 columns and 9 diagonals of 5..=9 tiles in both directions
+        assert_eq!(board(9, 9, 5).get_lines().len(), 36);
+        // 5 rows, 10 columns and 6 diagonals of 5 tiles in both directions
+        assert_eq!(board(10, 5, 5).get_lines().len(), 27);
+        // only the main diagonals are long enough
+        assert_eq!(board(5, 5, 5).get_lines().len(), 12);
+        // no line fits into the board
+        assert!(board(4, 4, 5).get_lines().is_empty());
+    }
+
+    #[test]
+    fn get_lines_cover_every_tile_in_every_direction() {
+        for (width, height) in [(1, 1), (2, 7), (5, 5), (9, 9), (10, 3), (10, 10)] {
+            let board = board(width, height, 1);
+            let lines = board.get_lines();
+            let diagonals = 2 * (width as usize + height as usize - 1);
+            assert_eq!(
+                lines.len(),
+                width as usize + height as usize + diagonals,
+                "{width}x{height}"
+            );
+
+            for coord in board.coordinates() {
+                let count = lines.iter().filter(|line| line.contains(&coord)).count();
+                assert_eq!(count, 4, "{coord:?} on {width}x{height}");
+            }
+            for line in &lines {
+                for pair in line.windows(2) {
+                    let dx = pair[1].0 as i32 - pair[0].0 as i32;
+                    let dy = pair[1].1 as i32 - pair[0].1 as i32;
+                    assert!(dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn despawn_lines_of_five_six_and_nine() {
+        let mut five = Board::default();
+        put(&mut five, Red, (0..5).map(|x| (x, 0)));
+        assert_eq!(lengths(&five.get_balls_for_despawn()), vec![5]);
+
+        let mut six = Board::default();
+        put(&mut six, Blue, (2..8).map(|y| (4, y)));
+        assert_eq!(lengths(&six.get_balls_for_despawn()), vec![6]);
+
+        let mut nine = Board::default();
+        put(&mut nine, Green, (0..9).map(|i| (i, i)));
+        let lines = nine.get_balls_for_despawn();
+        assert_eq!(lengths(&lines), vec![9]);
+        assert_eq!(lines[0].first(), Some(&Coordinates(0, 0)));
+        assert_eq!(lines[0].last(), Some(&Coordinates(8, 8)));
+    }
+
+    #[test]
+    fn keep_short_and_mixed_lines() {
+        let mut board = Board::default();
+        put(&mut board, Red, (0..4).map(|x| (x, 0)));
+        // a gap splits the line
+        put(&mut board, Red, [(0, 2), (1, 2), (3, 2), (4, 2), (5, 2)]);
+        // other color in the middle
+        put(
+            &mut board,
+            Red,
+            [(0, 4), (1, 4), (2, 4), (3, 4), (5, 4), (6, 4)],
+        );
+        put(&mut board, Blue, [(4, 4)]);
+        assert!(board.get_balls_for_despawn().is_empty());
+    }
+
+    #[test]
+    fn despawn_only_the_balls_of_the_line_color() {
+        let mut board = Board::default();
+        put(&mut board, Yellow, [(0, 3), (6, 3)]);
+        put(&mut board, Red, (1..6).map(|x| (x, 3)));
+        let lines = board.get_balls_for_despawn();
+        assert_eq!(lengths(&lines), vec![5]);
+        assert!(lines[0].iter().all(|coord| board.get(coord) == Some(Red)));
+    }
+
+    #[test]
+    fn despawn_crossing_lines_as_one_shape() {
+        let mut board = Board::default();
+        put(&mut board, Purple, (2..7).map(|x| (x, 4)));
+        put(&mut board, Purple, (0..5).map(|i| (i + 2, i)));
+        let lines = board.get_balls_for_despawn();
+        assert_eq!(lengths(&lines), vec![5, 5]);
+
+        let shapes = board.get_cleared_shapes();
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes[0].lines.len(), 2);
+        assert_eq!(shapes[0].balls.len(), 9);
+        assert_eq!(shapes[0].center(), Coordinates(6, 4));
+    }
+
+    #[test]
+    fn despawn_separate_lines_as_separate_shapes() {
+        let mut board = Board::default();
+        put(&mut board, Red, (0..5).map(|x| (x, 0)));
+        put(&mut board, Red, (0..5).map(|x| (x, 8)));
+        let shapes = board.get_cleared_shapes();
+        assert_eq!(shapes.len(), 2);
+        assert!(shapes.iter().all(|shape| shape.balls.len() == 5));
+    }
+
+    #[test]
+    fn despawn_lines_on_the_edges() {
+        let mut board = Board::default();
+        // last column, bottom row end and the anti-diagonal through the corners
+        put(&mut board, Cyan, (4..9).map(|y| (8, y)));
+        put(&mut board, Brown, (3..8).map(|x| (x, 8)));
+        put(&mut board, Green, (0..9).map(|i| (i, 8 - i)));
+        board.set(Coordinates(8, 3), Some(Brown));
+        assert_eq!(lengths(&board.get_balls_for_despawn()), vec![5, 5, 9]);
+
+        let mut small = self::board(5, 5, 5);
+        put(&mut small, Red, (0..5).map(|i| (4 - i, i)));
+        assert_eq!(lengths(&small.get_balls_for_despawn()), vec![5]);
+    }
+
+    #[test]
+    fn path_to_adjacent_tile() {
+        let mut board = Board::default();
+        put(&mut board, Red, [(4, 4)]);
+        assert_eq!(
+            board.get_path_to_move(&Coordinates(4, 4), &Coordinates(4, 5)),
+            Some(vec![Coordinates(4, 4), Coordinates(4, 5)])
+        );
+    }
+
+    #[test]
+    fn no_path_to_blocked_tile() {
+        let mut board = Board::default();
+        put(&mut board, Red, [(0, 0)]);
+        put(&mut board, Blue, [(7, 8), (8, 7)]);
+        assert_eq!(
+            board.get_path_to_move(&Coordinates(0, 0), &Coordinates(8, 8)),
+            None
+        );
+        // occupied target
+        assert_eq!(
+            board.get_path_to_move(&Coordinates(0, 0), &Coordinates(7, 8)),
+            None
+        );
+        // the ball can't move diagonally
+        put(&mut board, Blue, [(1, 0), (0, 1)]);
+        assert_eq!(
+            board.get_path_to_move(&Coordinates(0, 0), &Coordinates(1, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn shortest_path_through_maze() {
+        // walls with gaps at the opposite ends
+        let mut board = Board::default();
+        put(&mut board, Red, [(0, 0)]);
+        put(&mut board, Blue, (0..8).map(|y| (1, y)));
+        put(&mut board, Blue, (1..9).map(|y| (3, y)));
+        put(&mut board, Blue, (0..8).map(|y| (5, y)));
+
+        let path = board
+            .get_path_to_move(&Coordinates(0, 0), &Coordinates(8, 0))
+            .expect("Path not found");
+        assert_eq!(path.first(), Some(&Coordinates(0, 0)));
+        assert_eq!(path.last(), Some(&Coordinates(8, 0)));
+        // down, up and down the corridors, then to the corner: 40 steps from the start tile
+        assert_eq!(path.len(), 8 + 2 + 8 + 2 + 8 + 2 + 10 + 1);
+        for pair in path.windows(2) {
+            let distance = pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1);
+            assert_eq!(distance, 1);
+            assert_eq!(board.get(&pair[1]), None);
+        }
+    }
+
+    #[test]
+    fn coordinates_of_the_board() {
+        let board = board(3, 2, 2);
+        let coords: Vec<_> = board.coordinates().collect();
+        assert_eq!(coords.len(), 6);
+        assert_eq!(coords[0], Coordinates(0, 0));
+        assert_eq!(coords[1], Coordinates(0, 1));
+        assert_eq!(coords[5], Coordinates(2, 1));
+
+        assert!(board.contains(&Coordinates(2, 1)));
+        assert!(!board.contains(&Coordinates(3, 0)));
+        assert!(!board.contains(&Coordinates(0, 2)));
+        assert_eq!(board.get(&Coordinates(9, 9)), None);
+    }
+
+    #[test]
+    fn set_and_get_every_tile() {
+        let mut board = board(10, 7, 5);
+        for (index, coord) in board
+            .coordinates()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .enumerate()
+        {
+            board.set(coord, Some(BallColor::ALL[index % BallColor::ALL.len()]));
+        }
+        for (index, coord) in board.coordinates().enumerate() {
+            assert_eq!(
+                board.get(&coord),
+                Some(BallColor::ALL[index % BallColor::ALL.len()])
+            );
+        }
+        assert!(board.free_tiles().is_empty());
+    }
+}
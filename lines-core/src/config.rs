@@ -0,0 +1,147 @@
+use std::fmt;
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::system::Resource;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{BallColor, ScoringRule};
+
+/// Rules of a game: board size, count of colors, line length, spawned balls and scoring
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameConfig {
+    pub width: u8,
+    pub height: u8,
+    pub colors: u8,
+    // minimal count of balls with the same color in a row to clear a line
+    pub line_length: usize,
+    // count of balls spawned after a move without cleared lines
+    pub balls_per_turn: usize,
+    pub starting_balls: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scoring: ScoringRule,
+}
+
+impl Default for GameConfig {
+    // classic Lines: 9x9 board, 7 colors, 5 balls in a row, 3 new balls per turn
+    fn default() -> Self {
+        Self {
+            width: 9,
+            height: 9,
+            colors: 7,
+            line_length: 5,
+            balls_per_turn: 3,
+            starting_balls: 3,
+            scoring: ScoringRule::Standard,
+        }
+    }
+}
+
+/// Named configs with their own leader boards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Preset {
+    Classic,
+    Easy,
+    Hard,
+    Mini,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [Preset::Classic, Preset::Easy, Preset::Hard, Preset::Mini];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Classic => "Classic",
+            Preset::Easy => "Easy",
+            Preset::Hard => "Hard",
+            Preset::Mini => "Mini",
+        }
+    }
+
+    pub fn config(&self) -> GameConfig {
+        let classic = GameConfig::default();
+        match self {
+            Preset::Classic => classic,
+            Preset::Easy => GameConfig {
+                colors: 6,
+                ..classic
+            },
+            Preset::Hard => GameConfig {
+                colors: 8,
+                balls_per_turn: 4,
+                ..classic
+            },
+            Preset::Mini => GameConfig {
+                width: 7,
+                height: 7,
+                line_length: 4,
+                ..classic
+            },
+        }
+    }
+
+    // preset with exactly the same rules, custom configs have none
+    pub fn from_config(config: &GameConfig) -> Option<Preset> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.config() == *config)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    BoardSize,
+    Colors,
+    LineLength,
+    BallsPerTurn,
+    StartingBalls,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ConfigError::BoardSize => "Board should have at least 2 tiles on each side",
+            ConfigError::Colors => {
+                return write!(
+                    f,
+                    "Count of colors should be from 1 to {}",
+                    BallColor::ALL.len()
+                );
+            }
+            ConfigError::LineLength => "Line should be from 2 balls to the board side",
+            ConfigError::BallsPerTurn => "At least one ball should be spawned per turn",
+            ConfigError::StartingBalls => "Starting balls should leave free tiles on the board",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GameConfig {
+    pub fn tile_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.width < 2 || self.height < 2 {
+            return Err(ConfigError::BoardSize);
+        }
+        if self.colors == 0 || self.colors as usize > BallColor::ALL.len() {
+            return Err(ConfigError::Colors);
+        }
+        if self.line_length < 2 || self.line_length > self.width.max(self.height) as usize {
+            return Err(ConfigError::LineLength);
+        }
+        if self.balls_per_turn == 0 {
+            return Err(ConfigError::BallsPerTurn);
+        }
+        if self.starting_balls == 0 || self.starting_balls >= self.tile_count() {
+            return Err(ConfigError::StartingBalls);
+        }
+        Ok(())
+    }
+}
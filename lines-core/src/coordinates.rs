@@ -0,0 +1,48 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::component::Component;
+
+/// Position of a tile on the board: column and row
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Coordinates(pub u8, pub u8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn order_by_column_then_row() {
+        let mut coords = vec![Coordinates(1, 0), Coordinates(0, 2), Coordinates(0, 1)];
+        coords.sort();
+        assert_eq!(
+            coords,
+            vec![Coordinates(0, 1), Coordinates(0, 2), Coordinates(1, 0)]
+        );
+    }
+
+    #[test]
+    fn column_and_row_are_not_interchangeable() {
+        let coords: HashSet<_> = [Coordinates(1, 2), Coordinates(2, 1), Coordinates(1, 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(coords.len(), 2);
+        assert_ne!(Coordinates(1, 2), Coordinates(2, 1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_as_pair() {
+        let json = serde_json::to_string(&Coordinates(3, 8)).unwrap();
+        assert_eq!(json, "[3,8]");
+        assert_eq!(
+            serde_json::from_str::<Coordinates>(&json).unwrap(),
+            Coordinates(3, 8)
+        );
+        assert!(serde_json::from_str::<Coordinates>("[3,256]").is_err());
+    }
+}
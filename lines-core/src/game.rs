@@ -0,0 +1,557 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::history::Snapshot;
+use crate::{
+    best_move, BallColor, Board, ClearedShape, Coordinates, GameConfig, GameRng, Hint, Turn,
+};
+
+/// Change of the game state, in the order it happened during a turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameEvent {
+    /// Ball moved along the path. The first tile is the start, the last one is the target
+    Moved(Vec<Coordinates>),
+    /// New ball appeared on the tile
+    Spawned(Coordinates, BallColor),
+    /// Line or crossing lines of balls removed from the board and the score for them
+    Cleared(ClearedShape, u32),
+    /// No free tiles left on the board
+    GameOver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    GameOver,
+    EmptyTile,
+    OccupiedTile,
+    NoPath,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            MoveError::GameOver => "Game is over",
+            MoveError::EmptyTile => "No ball on the start tile",
+            MoveError::OccupiedTile => "Target tile is occupied",
+            MoveError::NoPath => "No path to the target tile",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Game {
+    #[cfg_attr(feature = "serde", serde(default))]
+    config: GameConfig,
+    board: Board,
+    next_balls: Vec<BallColor>,
+    score: u32,
+    is_over: bool,
+    rng: GameRng,
+    history: Vec<Turn>,
+    undone: Vec<Turn>,
+    undo_limit: Option<usize>,
+    undo_count: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    hint_limit: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    hint_count: u32,
+    // count of the last moves in a row which cleared lines
+    #[cfg_attr(feature = "serde", serde(default))]
+    streak: u32,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Game {
+    // the same seed and moves always give the same game
+    pub fn new(seed: u64) -> Self {
+        Self::with_config(GameConfig::default(), seed)
+    }
+
+    pub fn with_config(config: GameConfig, seed: u64) -> Self {
+        let mut rng = GameRng::new(seed);
+        Self {
+            config,
+            board: Board::new(&config),
+            next_balls: Self::random_colors(&config, config.balls_per_turn, &mut rng),
+            score: 0,
+            is_over: false,
+            rng,
+            history: vec![],
+            undone: vec![],
+            undo_limit: None,
+            undo_count: 0,
+            hint_limit: None,
+            hint_count: 0,
+            streak: 0,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    // colors of balls which will be spawned after the next move
+    pub fn next_balls(&self) -> &[BallColor] {
+        &self.next_balls
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_over
+    }
+
+    // journal of the moves made in this game
+    pub fn history(&self) -> &[Turn] {
+        &self.history
+    }
+
+    // count of undone moves during this game
+    pub fn undo_count(&self) -> u32 {
+        self.undo_count
+    }
+
+    // max count of last moves which can be undone, `None` for unlimited
+    pub fn set_undo_limit(&mut self, limit: Option<usize>) {
+        self.undo_limit = limit;
+        self.forget_old_snapshots();
+    }
+
+    // count of hints shown during this game
+    pub fn hint_count(&self) -> u32 {
+        self.hint_count
+    }
+
+    // max count of hints in this game, `None` for unlimited
+    pub fn set_hint_limit(&mut self, limit: Option<u32>) {
+        self.hint_limit = limit;
+    }
+
+    // state of the loaded game fits its rules, so moves can't go out of the board
+    pub fn is_valid(&self) -> bool {
+        let colors = &BallColor::ALL[..(self.config.colors as usize).min(BallColor::ALL.len())];
+        self.config.validate().is_ok()
+            && self.board.matches(&self.config)
+            && self.next_balls.len() == self.config.balls_per_turn
+            && self.next_balls.iter().all(|color| colors.contains(color))
+            && self
+                .history
+                .iter()
+                .chain(&self.undone)
+                .filter_map(|turn| turn.before.as_deref())
+                .all(|before| before.board.matches(&self.config))
+    }
+
+    pub fn can_hint(&self) -> bool {
+        !self.is_over && self.hint_limit.is_none_or(|limit| self.hint_count < limit)
+    }
+
+    // best move of the current board, every found hint is counted
+    pub fn hint(&mut self) -> Option<Hint> {
+        if !self.can_hint() {
+            return None;
+        }
+        let hint = best_move(&self.board)?;
+        self.hint_count += 1;
+        Some(hint)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|turn| turn.before.is_some())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    // restore the game state before the last move
+    pub fn undo(&mut self) -> Option<&Turn> {
+        if !self.can_undo() {
+            return None;
+        }
+        let mut turn = self.history.pop()?;
+        let before = *turn.before.take()?;
+
+        self.board = before.board;
+        self.next_balls = before.next_balls;
+        self.score = before.score;
+        self.is_over = before.is_over;
+        self.rng = before.rng;
+        self.streak = before.streak;
+        self.undo_count += 1;
+
+        self.undone.push(turn);
+        self.undone.last()
+    }
+
+    // make the last undone move again. The game state is restored with the random
+    // generator, so the move gives the same result
+    pub fn redo(&mut self) -> Option<Vec<GameEvent>> {
+        let turn = self.undone.pop()?;
+        match self.apply_move(turn.from, turn.to) {
+            Ok(events) => Some(events),
+            Err(_) => {
+                self.undone.clear();
+                None
+            }
+        }
+    }
+
+    // spawn startup balls
+    pub fn start(&mut self) -> Vec<GameEvent> {
+        let mut events = vec![];
+        let colors = Self::random_colors(&self.config, self.config.starting_balls, &mut self.rng);
+        self.spawn_balls(colors, &mut events);
+        events
+    }
+
+    pub fn make_move(
+        &mut self,
+        from: Coordinates,
+        to: Coordinates,
+    ) -> Result<Vec<GameEvent>, MoveError> {
+        let events = self.apply_move(from, to)?;
+        self.undone.clear();
+        Ok(events)
+    }
+
+    fn apply_move(
+        &mut self,
+        from: Coordinates,
+        to: Coordinates,
+    ) -> Result<Vec<GameEvent>, MoveError> {
+        if self.is_over {
+            return Err(MoveError::GameOver);
+        }
+        let ball = self.board.get(&from).ok_or(MoveError::EmptyTile)?;
+        if self.board.get(&to).is_some() {
+            return Err(MoveError::OccupiedTile);
+        }
+        let path = self
+            .board
+            .get_path_to_move(&from, &to)
+            .ok_or(MoveError::NoPath)?;
+
+        let before = self.snapshot();
+        let score = self.score;
+
+        self.board.set(from, None);
+        self.board.set(to, Some(ball));
+
+        let mut events = vec![GameEvent::Moved(path)];
+
+        if self.clear_lines(self.streak, &mut events) {
+            self.streak += 1;
+        } else {
+            self.streak = 0;
+            let next_balls =
+                Self::random_colors(&self.config, self.config.balls_per_turn, &mut self.rng);
+            let colors = std::mem::replace(&mut self.next_balls, next_balls);
+            self.spawn_balls(colors, &mut events);
+        }
+
+        self.history.push(Turn {
+            from,
+            to,
+            events: events.clone(),
+            score: self.score - score,
+            before: Some(Box::new(before)),
+        });
+        self.forget_old_snapshots();
+
+        Ok(events)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board.clone(),
+            next_balls: self.next_balls.clone(),
+            score: self.score,
+            is_over: self.is_over,
+            rng: self.rng.clone(),
+            streak: self.streak,
+        }
+    }
+
+    // moves out of the undo limit can't be undone, so their state isn't needed anymore
+    fn forget_old_snapshots(&mut self) {
+        if let Some(limit) = self.undo_limit {
+            let len = self.history.len();
+            for turn in self.history.iter_mut().take(len.saturating_sub(limit)) {
+                turn.before = None;
+            }
+        }
+    }
+
+    fn random_colors(config: &GameConfig, count: usize, rng: &mut GameRng) -> Vec<BallColor> {
+        (0..count)
+            .map(|_| BallColor::random(rng, config.colors))
+            .collect()
+    }
+
+    fn spawn_balls(&mut self, colors: Vec<BallColor>, events: &mut Vec<GameEvent>) {
+        for color in colors {
+            let Some(coord) = self.board.get_free_tile(&mut self.rng) else {
+                break;
+            };
+            self.board.set(coord, Some(color));
+            events.push(GameEvent::Spawned(coord, color));
+        }
+
+        // mb new combinations after spawn new balls, they don't continue the streak
+        self.clear_lines(0, events);
+
+        if self.board.free_tiles().is_empty() {
+            self.is_over = true;
+            events.push(GameEvent::GameOver);
+        }
+    }
+
+    // remove lines from the board and increment score by the scoring rule of the game,
+    // returns true if any line was cleared
+    fn clear_lines(&mut self, streak: u32, events: &mut Vec<GameEvent>) -> bool {
+        let shapes = self.board.get_cleared_shapes();
+        let scores = self
+            .config
+            .scoring
+            .scoring()
+            .score(&shapes, self.config.line_length, streak);
+        let cleared = !shapes.is_empty();
+
+        for (shape, score) in shapes.into_iter().zip(scores) {
+            self.score += score;
+
+            for coord in &shape.balls {
+                self.board.set(*coord, None);
+            }
+            events.push(GameEvent::Cleared(shape, score));
+        }
+
+        cleared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Random, Strategy};
+
+    // 4x4 board of 4 colors where no line of 4 can be made, with the free top left tile
+    fn full_game(balls_per_turn: usize) -> Game {
+        let config = GameConfig {
+            width: 4,
+            height: 4,
+            colors: 4,
+            line_length: 4,
+            balls_per_turn,
+            starting_balls: 1,
+            ..GameConfig::default()
+        };
+        let mut game = Game::with_config(config, 7);
+        for coord in game.board.coordinates().collect::<Vec<_>>() {
+            let color = BallColor::ALL[(coord.0 as usize + 2 * coord.1 as usize) % 4];
+            game.board.set(coord, Some(color));
+        }
+        game.board.set(Coordinates(0, 0), None);
+        game
+    }
+
+    // started game with the moves of the random player
+    fn played_game(seed: u64, moves: usize) -> Game {
+        let mut game = Game::new(seed);
+        game.start();
+        let mut player = Random::new(seed);
+        for _ in 0..moves {
+            let (from, to) = player.choose_move(&game).expect("No move");
+            game.make_move(from, to).expect("Move failed");
+        }
+        game
+    }
+
+    #[test]
+    fn game_is_over_when_spawned_balls_fill_the_board() {
+        let mut game = full_game(3);
+        let events = game
+            .make_move(Coordinates(1, 0), Coordinates(0, 0))
+            .expect("Move failed");
+
+        assert!(game.is_over());
+        assert!(game.board().free_tiles().is_empty());
+        assert_eq!(events.last(), Some(&GameEvent::GameOver));
+        // only one ball fits into the board
+        let spawned = events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::Spawned(..)))
+            .count();
+        assert_eq!(spawned, 1);
+
+        assert_eq!(
+            game.make_move(Coordinates(0, 0), Coordinates(1, 0)),
+            Err(MoveError::GameOver)
+        );
+    }
+
+    #[test]
+    fn game_goes_on_while_free_tiles_are_left() {
+        let mut game = full_game(1);
+        game.board.set(Coordinates(3, 3), None);
+        let events = game
+            .make_move(Coordinates(1, 0), Coordinates(0, 0))
+            .expect("Move failed");
+
+        assert!(!game.is_over());
+        assert_eq!(game.board().free_tiles().len(), 1);
+        assert!(!events.contains(&GameEvent::GameOver));
+    }
+
+    #[test]
+    fn start_spawns_starting_balls() {
+        let mut game = Game::new(1);
+        let events = game.start();
+        assert_eq!(events.len(), GameConfig::default().starting_balls);
+        assert_eq!(game.board().balls().count(), events.len());
+        assert!(!game.is_over());
+    }
+
+    #[test]
+    fn cleared_line_doesnt_spawn_balls() {
+        let mut game = Game::new(3);
+        for x in 0..4 {
+            game.board.set(Coordinates(x, 0), Some(BallColor::Red));
+        }
+        game.board.set(Coordinates(4, 5), Some(BallColor::Red));
+
+        let events = game
+            .make_move(Coordinates(4, 5), Coordinates(4, 0))
+            .expect("Move failed");
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[1], GameEvent::Cleared(shape, 10) if shape.balls.len() == 5));
+        assert_eq!(game.score(), 10);
+        assert_eq!(game.board().balls().count(), 0);
+    }
+
+    #[test]
+    fn invalid_moves_are_rejected() {
+        let mut game = Game::new(5);
+        game.board.set(Coordinates(0, 0), Some(BallColor::Red));
+        game.board.set(Coordinates(1, 0), Some(BallColor::Blue));
+        game.board.set(Coordinates(0, 1), Some(BallColor::Blue));
+
+        let moves = [
+            ((5, 5), (6, 6), MoveError::EmptyTile),
+            ((0, 0), (1, 0), MoveError::OccupiedTile),
+            ((0, 0), (8, 8), MoveError::NoPath),
+        ];
+        for ((fx, fy), (tx, ty), error) in moves {
+            assert_eq!(
+                game.make_move(Coordinates(fx, fy), Coordinates(tx, ty)),
+                Err(error)
+            );
+        }
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn redo_repeats_the_undone_move() {
+        let mut game = played_game(11, 5);
+        let turn = game.history().last().cloned().expect("No moves");
+        let (board, next_balls, score) = (
+            game.board().clone(),
+            game.next_balls().to_vec(),
+            game.score(),
+        );
+
+        let undone = game.undo().cloned().expect("Undo failed");
+        assert_eq!(undone.events, turn.events);
+        assert_eq!(game.history().len(), 4);
+        assert_eq!(game.undo_count(), 1);
+        assert!(game.can_redo());
+
+        let events = game.redo().expect("Redo failed");
+        assert_eq!(events, turn.events);
+        assert_eq!(game.board(), &board);
+        assert_eq!(game.next_balls(), next_balls);
+        assert_eq!(game.score(), score);
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn undo_stops_at_the_limit() {
+        let mut game = played_game(12, 2);
+        game.set_undo_limit(Some(2));
+        let mut player = Random::new(12);
+        for _ in 0..3 {
+            let (from, to) = player.choose_move(&game).expect("No move");
+            game.make_move(from, to).expect("Move failed");
+        }
+
+        assert!(game.undo().is_some());
+        assert!(game.undo().is_some());
+        assert!(!game.can_undo());
+        assert!(game.undo().is_none());
+        assert_eq!(game.history().len(), 3);
+        assert_eq!(game.undo_count(), 2);
+    }
+
+    #[test]
+    fn new_move_clears_undone_moves() {
+        let mut game = played_game(13, 3);
+        game.undo().expect("Undo failed");
+        assert!(game.can_redo());
+
+        let (from, to) = Random::new(99).choose_move(&game).expect("No move");
+        game.make_move(from, to).expect("Move failed");
+        assert!(!game.can_redo());
+        assert!(game.redo().is_none());
+        assert_eq!(game.history().len(), 3);
+    }
+
+    #[test]
+    fn broken_state_is_invalid() {
+        let game = played_game(14, 3);
+        assert!(game.is_valid());
+
+        let mut broken = game.clone();
+        broken.board = Board::new(&GameConfig {
+            width: 5,
+            ..GameConfig::default()
+        });
+        assert!(!broken.is_valid());
+
+        let mut broken = game.clone();
+        broken.config.line_length = 0;
+        assert!(!broken.is_valid());
+
+        let mut broken = game.clone();
+        broken.next_balls = vec![BallColor::Orange; 3];
+        assert!(!broken.is_valid());
+
+        let mut broken = game;
+        broken.next_balls.pop();
+        assert!(!broken.is_valid());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{BallColor, Board, Coordinates};
+
+// directions of the lines: horizontal, vertical and both diagonals
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+// value of a move which clears a line, higher than any line extension
+const CLEAR_VALUE: i32 = 1000;
+
+/// Move suggested by the evaluator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    // the higher the better, moves clearing lines are valued the most
+    pub value: i32,
+}
+
+/// Best reachable move of the board: the one which clears the most balls,
+/// or extends the longest line which can still be completed
+pub fn best_move(board: &Board) -> Option<Hint> {
+    let balls: Vec<_> = board.balls().collect();
+
+    balls
+        .iter()
+        .flat_map(|(from, _)| {
+            board.reachable_tiles(from).into_iter().map(|to| Hint {
+                from: *from,
+                to,
+                value: evaluate_move(board, from, &to),
+            })
+        })
+        .max_by_key(|hint| hint.value)
+}
+
+/// Value of the move of the ball to the reachable empty tile
+pub fn evaluate_move(board: &Board, from: &Coordinates, to: &Coordinates) -> i32 {
+    let Some(color) = board.get(from) else {
+        return i32::MIN;
+    };
+    let before = line_potential(board, from, color);
+
+    let mut board = board.clone();
+    board.set(*from, None);
+    board.set(*to, Some(color));
+
+    let cleared: usize = board
+        .get_cleared_shapes()
+        .iter()
+        .map(|shape| shape.balls.len())
+        .sum();
+    if cleared > 0 {
+        return CLEAR_VALUE + cleared as i32;
+    }
+
+    // prefer growing lines without breaking the existing ones
+    line_potential(&board, to, color) - before
+}
+
+// the longest run of the same color through the ball, which has room to become a full line
+pub fn line_potential(board: &Board, coord: &Coordinates, color: BallColor) -> i32 {
+    DIRECTIONS
+        .iter()
+        .map(|&(dx, dy)| {
+            let run = 1
+                + count(board, coord, (dx, dy), |c| c == Some(color))
+                + count(board, coord, (-dx, -dy), |c| c == Some(color));
+            let room = 1
+                + count(board, coord, (dx, dy), |c| c.is_none() || c == Some(color))
+                + count(board, coord, (-dx, -dy), |c| {
+                    c.is_none() || c == Some(color)
+                });
+
+            if room >= board.line_length() {
+                run as i32
+            } else {
+                0
+            }
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+// count of the tiles in the direction from the tile, which match the condition
+fn count(
+    board: &Board,
+    coord: &Coordinates,
+    (dx, dy): (i32, i32),
+    matches: impl Fn(Option<BallColor>) -> bool,
+) -> usize {
+    let mut count = 0;
+    let (mut x, mut y) = (coord.0 as i32 + dx, coord.1 as i32 + dy);
+
+    while x >= 0 && y >= 0 && x < board.width() as i32 && y < board.height() as i32 {
+        if !matches(board.get(&Coordinates(x as u8, y as u8))) {
+            break;
+        }
+        count += 1;
+        x += dx;
+        y += dy;
+    }
+
+    count
+}
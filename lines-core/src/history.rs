@@ -0,0 +1,44 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{BallColor, Board, ClearedShape, Coordinates, GameEvent, GameRng};
+
+/// Journal record of one move: the move itself, cleared lines, spawned balls and the score delta
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Turn {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    pub events: Vec<GameEvent>,
+    pub score: u32,
+    // state of the game before the move, kept only while the move can be undone
+    pub(crate) before: Option<Box<Snapshot>>,
+}
+
+impl Turn {
+    pub fn cleared_shapes(&self) -> impl Iterator<Item = &ClearedShape> {
+        self.events.iter().filter_map(|event| match event {
+            GameEvent::Cleared(shape, _) => Some(shape),
+            _ => None,
+        })
+    }
+
+    pub fn spawned_balls(&self) -> impl Iterator<Item = (Coordinates, BallColor)> + '_ {
+        self.events.iter().filter_map(|event| match event {
+            GameEvent::Spawned(coord, color) => Some((*coord, *color)),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct Snapshot {
+    pub board: Board,
+    pub next_balls: Vec<BallColor>,
+    pub score: u32,
+    pub is_over: bool,
+    pub rng: GameRng,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub streak: u32,
+}
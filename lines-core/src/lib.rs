@@ -0,0 +1,30 @@
+//! Rules of the Lines game: board, moves, spawning, line clearing, scoring and game over.
+//!
+//! The crate does not depend on Bevy, so the rules can be tested, simulated
+//! or played by bots without a window.
+
+mod ai;
+mod ball;
+mod board;
+mod config;
+mod coordinates;
+mod game;
+mod hint;
+mod history;
+mod replay;
+mod rng;
+mod scoring;
+mod shape;
+
+pub use ai::{autoplay, Ai, Greedy, Random, Strategy};
+pub use ball::BallColor;
+pub use board::Board;
+pub use config::{ConfigError, GameConfig, Preset};
+pub use coordinates::Coordinates;
+pub use game::{Game, GameEvent, MoveError};
+pub use hint::{best_move, evaluate_move, line_potential, Hint};
+pub use history::Turn;
+pub use replay::{Replay, ReplayError, REPLAY_FORMAT};
+pub use rng::GameRng;
+pub use scoring::{ComboStreak, Lines98, MultiLineBonus, Scoring, ScoringRule, Standard};
+pub use shape::ClearedShape;
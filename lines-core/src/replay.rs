@@ -0,0 +1,130 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{ConfigError, Coordinates, Game, GameConfig, MoveError};
+
+/// Format of the replay, increment on incompatible changes
+pub const REPLAY_FORMAT: u32 = 1;
+
+/// Compact record of a game: the seed and the moves are enough to play it again
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Replay {
+    pub format: u32,
+    // version of the rules which played the game
+    pub version: String,
+    pub seed: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub config: GameConfig,
+    pub moves: Vec<(Coordinates, Coordinates)>,
+    pub score: u32,
+    // count of hints shown during the game, games with hints are flagged
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hints: u32,
+    // count of undone moves, only their final moves are recorded
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub undos: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    UnsupportedFormat(u32),
+    InvalidConfig(ConfigError),
+    InvalidMove { index: usize, error: MoveError },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::UnsupportedFormat(format) => {
+                write!(f, "Unsupported replay format {format}")
+            }
+            ReplayError::InvalidConfig(error) => write!(f, "Invalid game config: {error}"),
+            ReplayError::InvalidMove { index, error } => write!(f, "Move {index}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Replay {
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            format: REPLAY_FORMAT,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            seed: game.seed(),
+            config: *game.config(),
+            moves: game
+                .history()
+                .iter()
+                .map(|turn| (turn.from, turn.to))
+                .collect(),
+            score: game.score(),
+            hints: game.hint_count(),
+            undos: game.undo_count(),
+        }
+    }
+
+    pub fn is_hinted(&self) -> bool {
+        self.hints > 0
+    }
+
+    // game with startup balls and without moves, every move of it can be undone
+    pub fn start_game(&self) -> Result<Game, ReplayError> {
+        if self.format != REPLAY_FORMAT {
+            return Err(ReplayError::UnsupportedFormat(self.format));
+        }
+        self.config.validate().map_err(ReplayError::InvalidConfig)?;
+        let mut game = Game::with_config(self.config, self.seed);
+        game.set_undo_limit(None);
+        game.start();
+        Ok(game)
+    }
+
+    // play all moves of the replay
+    pub fn play(&self) -> Result<Game, ReplayError> {
+        let mut game = self.start_game()?;
+        for (index, (from, to)) in self.moves.iter().enumerate() {
+            game.make_move(*from, *to)
+                .map_err(|error| ReplayError::InvalidMove { index, error })?;
+        }
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::best_move;
+
+    #[test]
+    fn replay_keeps_hints() {
+        let mut game = Game::new(4);
+        game.start();
+        assert!(!Replay::from_game(&game).is_hinted());
+
+        let hint = game.hint().expect("No hint");
+        game.make_move(hint.from, hint.to).expect("Move failed");
+        let replay = Replay::from_game(&game);
+        assert_eq!(replay.hints, 1);
+        assert!(replay.is_hinted());
+        assert_eq!(replay.play().map(|game| game.score()), Ok(replay.score));
+    }
+
+    #[test]
+    fn replay_keeps_undos() {
+        let mut game = Game::new(5);
+        game.start();
+        for _ in 0..2 {
+            let hint = best_move(game.board()).expect("No move");
+            game.make_move(hint.from, hint.to).expect("Move failed");
+        }
+        game.undo().expect("Undo failed");
+
+        let replay = Replay::from_game(&game);
+        assert_eq!(replay.undos, 1);
+        assert_eq!(replay.moves.len(), 1);
+    }
+}
@@ -0,0 +1,229 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ClearedShape;
+
+/// Rule which turns the cleared lines into points
+pub trait Scoring {
+    /// Points of the line of `length` balls, `line_length` is the minimal length of the line
+    fn line_score(&self, length: usize, line_length: usize) -> u32;
+
+    /// Points of the shape. Crossing lines are scored as a single line of all their balls,
+    /// so the shared balls are counted once
+    fn shape_score(&self, shape: &ClearedShape, line_length: usize) -> u32 {
+        self.line_score(shape.balls.len(), line_length)
+    }
+
+    /// Points of every shape cleared at once. `streak` is count of the previous moves in a row
+    /// which cleared lines, every shape is scored by itself by default
+    fn score(&self, shapes: &[ClearedShape], line_length: usize, _streak: u32) -> Vec<u32> {
+        shapes
+            .iter()
+            .map(|shape| self.shape_score(shape, line_length))
+            .collect()
+    }
+}
+
+/// Two points for every ball of the line
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Standard;
+
+impl Scoring for Standard {
+    fn line_score(&self, length: usize, _line_length: usize) -> u32 {
+        length as u32 * 2
+    }
+}
+
+/// Color Lines 98 formula: 10 points for 5 balls, then 12, 18, 28 and 42 for 6, 7, 8 and 9 balls
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lines98;
+
+impl Scoring for Lines98 {
+    fn line_score(&self, length: usize, line_length: usize) -> u32 {
+        let extra = length.saturating_sub(line_length) as u32;
+        line_length as u32 * 2 + extra * extra * 2
+    }
+}
+
+/// Every line cleared at once multiplies the points of all of them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MultiLineBonus<S>(pub S);
+
+impl<S: Scoring> Scoring for MultiLineBonus<S> {
+    fn line_score(&self, length: usize, line_length: usize) -> u32 {
+        self.0.line_score(length, line_length)
+    }
+
+    fn shape_score(&self, shape: &ClearedShape, line_length: usize) -> u32 {
+        self.0.shape_score(shape, line_length)
+    }
+
+    fn score(&self, shapes: &[ClearedShape], line_length: usize, streak: u32) -> Vec<u32> {
+        let count = shapes.iter().map(|shape| shape.lines.len()).sum::<usize>() as u32;
+        self.0
+            .score(shapes, line_length, streak)
+            .into_iter()
+            .map(|score| score * count)
+            .collect()
+    }
+}
+
+/// Moves clearing lines in a row get a half of the points more for every previous move,
+/// up to the triple points
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComboStreak<S>(pub S);
+
+const MAX_STREAK: u32 = 4;
+
+impl<S: Scoring> Scoring for ComboStreak<S> {
+    fn line_score(&self, length: usize, line_length: usize) -> u32 {
+        self.0.line_score(length, line_length)
+    }
+
+    fn shape_score(&self, shape: &ClearedShape, line_length: usize) -> u32 {
+        self.0.shape_score(shape, line_length)
+    }
+
+    fn score(&self, shapes: &[ClearedShape], line_length: usize, streak: u32) -> Vec<u32> {
+        let streak = streak.min(MAX_STREAK);
+        self.0
+            .score(shapes, line_length, streak)
+            .into_iter()
+            .map(|score| score * (2 + streak) / 2)
+            .collect()
+    }
+}
+
+/// Scoring rules which can be chosen for a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScoringRule {
+    #[default]
+    Standard,
+    Lines98,
+    // Color Lines 98 formula with the multi-line bonus
+    MultiLine,
+    // Color Lines 98 formula with the multi-line bonus and combo streaks
+    Combo,
+}
+
+impl ScoringRule {
+    pub const ALL: [ScoringRule; 4] = [
+        ScoringRule::Standard,
+        ScoringRule::Lines98,
+        ScoringRule::MultiLine,
+        ScoringRule::Combo,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScoringRule::Standard => "Simple",
+            ScoringRule::Lines98 => "Lines 98",
+            ScoringRule::MultiLine => "Multi",
+            ScoringRule::Combo => "Combo",
+        }
+    }
+
+    pub fn scoring(&self) -> &'static dyn Scoring {
+        match self {
+            ScoringRule::Standard => &Standard,
+            ScoringRule::Lines98 => &Lines98,
+            ScoringRule::MultiLine => &MultiLineBonus(Lines98),
+            ScoringRule::Combo => &ComboStreak(MultiLineBonus(Lines98)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Coordinates;
+
+    // horizontal line of `length` balls on the row
+    fn line(length: u8, y: u8) -> Vec<Coordinates> {
+        (0..length).map(|x| Coordinates(x, y)).collect()
+    }
+
+    // row and column of 5 balls crossing at (2, 2), 9 balls at all
+    fn cross() -> ClearedShape {
+        let column = (0..5).map(|y| Coordinates(2, y)).collect();
+        ClearedShape::new(vec![line(5, 2), column])
+    }
+
+    #[test]
+    fn standard_and_lines98_points_by_line_length() {
+        let lengths = [
+            (5, 10, 10),
+            (6, 12, 12),
+            (7, 14, 18),
+            (8, 16, 28),
+            (9, 18, 42),
+        ];
+        for (length, standard, lines98) in lengths {
+            assert_eq!(Standard.line_score(length, 5), standard, "{length} balls");
+            assert_eq!(Lines98.line_score(length, 5), lines98, "{length} balls");
+        }
+        // shorter lines of the custom rules
+        assert_eq!(Lines98.line_score(4, 4), 8);
+        assert_eq!(Lines98.line_score(6, 4), 16);
+    }
+
+    #[test]
+    fn crossing_lines_count_shared_ball_once() {
+        let shape = cross();
+        assert_eq!(shape.balls.len(), 9);
+        assert_eq!(Standard.shape_score(&shape, 5), 18);
+        assert_eq!(Lines98.shape_score(&shape, 5), 42);
+    }
+
+    #[test]
+    fn multi_line_bonus_multiplies_by_line_count() {
+        let scoring = MultiLineBonus(Lines98);
+        let single = ClearedShape::new(vec![line(5, 0)]);
+        let apart = [single.clone(), ClearedShape::new(vec![line(6, 4)])];
+
+        let cases: [(&[ClearedShape], Vec<u32>); 3] = [
+            (&[single], vec![10]),
+            (&apart, vec![20, 24]),
+            (&[cross()], vec![84]),
+        ];
+        for (shapes, points) in cases {
+            assert_eq!(scoring.score(shapes, 5, 0), points);
+        }
+    }
+
+    #[test]
+    fn combo_streak_adds_half_points_up_to_triple() {
+        let scoring = ComboStreak(MultiLineBonus(Lines98));
+        let shapes = [ClearedShape::new(vec![line(5, 0)])];
+
+        let streaks = [
+            (0, 10),
+            (1, 15),
+            (2, 20),
+            (3, 25),
+            (4, 30),
+            (5, 30),
+            (20, 30),
+        ];
+        for (streak, points) in streaks {
+            assert_eq!(
+                scoring.score(&shapes, 5, streak),
+                vec![points],
+                "streak {streak}"
+            );
+        }
+        // the bonus of crossing lines is multiplied by the streak too
+        assert_eq!(scoring.score(&[cross()], 5, 2), vec![168]);
+    }
+
+    #[test]
+    fn rules_use_their_scorings() {
+        let shapes = [cross()];
+        let points: Vec<_> = ScoringRule::ALL
+            .iter()
+            .map(|rule| rule.scoring().score(&shapes, 5, 1))
+            .collect();
+        assert_eq!(points, [vec![18], vec![42], vec![84], vec![126]]);
+    }
+}
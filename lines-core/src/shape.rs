@@ -0,0 +1,58 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Coordinates;
+
+/// Lines cleared together because they share balls, e.g. a cross of a row and a diagonal.
+/// A single line is the shape too
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClearedShape {
+    pub lines: Vec<Vec<Coordinates>>,
+    // every ball of the lines once
+    pub balls: Vec<Coordinates>,
+}
+
+impl ClearedShape {
+    pub fn new(lines: Vec<Vec<Coordinates>>) -> Self {
+        let mut balls: Vec<Coordinates> = vec![];
+        for coord in lines.iter().flatten() {
+            if !balls.contains(coord) {
+                balls.push(*coord);
+            }
+        }
+        Self { lines, balls }
+    }
+
+    /// Group the lines which share balls into shapes
+    pub fn from_lines(lines: Vec<Vec<Coordinates>>) -> Vec<ClearedShape> {
+        let mut groups: Vec<Vec<Vec<Coordinates>>> = vec![];
+
+        for line in lines {
+            let (crossed, mut rest): (Vec<_>, Vec<_>) = groups
+                .into_iter()
+                .partition(|group| group.iter().flatten().any(|coord| line.contains(coord)));
+
+            // the line joins all groups it crosses
+            let mut group: Vec<_> = crossed.into_iter().flatten().collect();
+            group.push(line);
+            rest.push(group);
+            groups = rest;
+        }
+
+        groups.into_iter().map(ClearedShape::new).collect()
+    }
+
+    /// Ball shared by the lines of the shape, or the middle of the single line
+    pub fn center(&self) -> Coordinates {
+        let shared = self
+            .balls
+            .iter()
+            .find(|ball| self.lines.iter().filter(|line| line.contains(ball)).count() > 1);
+        shared.copied().unwrap_or(self.balls[self.balls.len() / 2])
+    }
+
+    pub fn is_crossing(&self) -> bool {
+        self.lines.len() > 1
+    }
+}
@@ -0,0 +1,193 @@
+use std::io::{BufRead, BufReader, Read, Write};
+
+// requests with the longer body are rejected, a replay of the long game takes about 20 kB
+const MAX_BODY: usize = 1 << 20;
+// the request line and the headers together
+const MAX_HEAD: usize = 8 << 10;
+
+/// Request line, the query and the body, the headers except the length aren't needed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        let body = serde_json::json!({ "error": message }).to_string();
+        Self { status, body }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            201 => "Created",
+            204 => "No Content",
+            400 => "Bad Request",
+            404 => "Not Found",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    // every origin may ask the server, the game in the browser is served from another one
+    pub fn write_to(&self, stream: &mut impl Write) -> std::io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             Access-Control-Allow-Origin: *\r\n\
+             Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+             Access-Control-Allow-Headers: Content-Type\r\n\
+             Connection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.body.len(),
+            self.body
+        )?;
+        stream.flush()
+    }
+}
+
+fn bad_request() -> Response {
+    Response::error(400, "Malformed request")
+}
+
+// line of the request head, `left` is the size of the head still allowed
+fn read_head_line(reader: &mut impl BufRead, left: &mut usize) -> Result<String, Response> {
+    let mut line = String::new();
+    let read = reader
+        .take(*left as u64)
+        .read_line(&mut line)
+        .map_err(|_| bad_request())?;
+    if !line.ends_with('\n') {
+        return Err(match read == *left {
+            true => Response::error(431, "Request head is too long"),
+            false => bad_request(),
+        });
+    }
+    *left -= read;
+    Ok(line)
+}
+
+pub fn read_request(stream: impl Read) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+    let mut left = MAX_HEAD;
+
+    let line = read_head_line(&mut reader, &mut left)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request());
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut length = 0;
+    loop {
+        let header = read_head_line(&mut reader, &mut left)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| bad_request())?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(Response::error(413, "Request is too long"));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|_| bad_request())?;
+
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                (key.to_string(), value.to_string())
+            })
+            .collect(),
+        body: String::from_utf8(body).map_err(|_| bad_request())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_post_request() {
+        let data = "POST /scores?preset=easy&limit=5 HTTP/1.1\r\n\
+                    Host: localhost\r\n\
+                    content-length: 7\r\n\r\n{\"a\":1}";
+        let request = read_request(data.as_bytes()).unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/scores");
+        assert_eq!(request.param("preset"), Some("easy"));
+        assert_eq!(request.param("limit"), Some("5"));
+        assert_eq!(request.param("name"), None);
+        assert_eq!(request.body, "{\"a\":1}");
+    }
+
+    #[test]
+    fn reject_malformed_request() {
+        assert_eq!(read_request("\r\n".as_bytes()).unwrap_err().status, 400);
+        let truncated = "POST /scores HTTP/1.1\r\nContent-Length: 10\r\n\r\n{}";
+        assert_eq!(read_request(truncated.as_bytes()).unwrap_err().status, 400);
+        let long = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY + 1
+        );
+        assert_eq!(read_request(long.as_bytes()).unwrap_err().status, 413);
+
+        // endless headers without the body
+        let headers = format!("GET / HTTP/1.1\r\n{}", "X-Header: value\r\n".repeat(1000));
+        assert_eq!(read_request(headers.as_bytes()).unwrap_err().status, 431);
+        let line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEAD));
+        assert_eq!(read_request(line.as_bytes()).unwrap_err().status, 431);
+    }
+
+    #[test]
+    fn write_response() {
+        let mut data = vec![];
+        Response::json(200, "[]".to_string())
+            .write_to(&mut data)
+            .unwrap();
+        let data = String::from_utf8(data).unwrap();
+
+        assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(data.contains("Content-Length: 2\r\n"));
+        assert!(data.ends_with("\r\n\r\n[]"));
+    }
+}
@@ -0,0 +1,174 @@
+//! Global leader board of the LAN: the games send their scores with the replays, and show
+//! the best games of every preset.
+//!
+//! `lines-server --addr 0.0.0.0:8080 --data scores.json --size 100`
+//!
+//! `GET /scores?preset=classic&limit=10` returns the best entries of the preset,
+//! `POST /scores` takes `{"name": "Ann", "replay": {...}}` and returns `{"rank": 3}`,
+//! the rank is null for the game too low for the table. The server plays the replay by the rules
+//! of `lines-core` and takes only the finished games with the same score and without undone
+//! moves, the hints of the replay flag the entry.
+
+mod http;
+mod store;
+
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use lines_core::Preset;
+
+use http::{read_request, Request, Response};
+use store::{verify, Store, Submission};
+
+const DEFAULT_LIMIT: usize = 10;
+
+// slow clients don't hold their threads forever
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// connections served at once, the others are answered that the server is busy
+const MAX_CONNECTIONS: usize = 64;
+
+struct Options {
+    addr: String,
+    data: PathBuf,
+    size: usize,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, String> {
+        let mut options = Self {
+            addr: "0.0.0.0:8080".to_string(),
+            data: PathBuf::from("scores.json"),
+            size: 100,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value of {arg}"));
+            match arg.as_str() {
+                "--addr" => options.addr = value()?,
+                "--data" => options.data = PathBuf::from(value()?),
+                "--size" => {
+                    let size = value()?;
+                    options.size = size.parse().map_err(|_| format!("Invalid number {size}"))?;
+                }
+                _ => return Err(format!("Unknown option {arg}")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+// the tables stay usable after the panic of another request
+fn lock(store: &Mutex<Store>) -> MutexGuard<'_, Store> {
+    store.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn handle(request: Request, store: &Mutex<Store>) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        // preflight of the browser before the post
+        ("OPTIONS", _) => Response::json(204, String::new()),
+        ("GET", "/scores") => {
+            let Some(preset) = request.param("preset").and_then(|name| {
+                Preset::ALL
+                    .into_iter()
+                    .find(|preset| preset.name().eq_ignore_ascii_case(name))
+            }) else {
+                return Response::error(400, "Unknown preset");
+            };
+            let limit = request
+                .param("limit")
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(DEFAULT_LIMIT);
+
+            let top = lock(store).top(preset, limit);
+            Response::json(200, serde_json::to_string(&top).unwrap_or_default())
+        }
+        ("POST", "/scores") => {
+            let submission = match serde_json::from_str::<Submission>(&request.body) {
+                Ok(submission) => submission,
+                Err(error) => return Response::error(400, &error.to_string()),
+            };
+            // the replay is played before the store is locked, so other requests don't wait
+            match verify(submission, now()) {
+                Ok(verified) => {
+                    let rank = lock(store).insert(verified);
+                    Response::json(201, serde_json::json!({ "rank": rank }).to_string())
+                }
+                Err(error) => Response::error(422, &error.to_string()),
+            }
+        }
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+// connection being served, released when its thread ends, even by the panic
+struct Connection(Arc<AtomicUsize>);
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn serve(mut stream: TcpStream, store: &Mutex<Store>) {
+    let _ = stream.set_read_timeout(Some(TIMEOUT));
+    let _ = stream.set_write_timeout(Some(TIMEOUT));
+
+    let response = match read_request(&stream) {
+        Ok(request) => handle(request, store),
+        Err(response) => response,
+    };
+    if let Err(error) = response.write_to(&mut stream) {
+        eprintln!("Can't answer the request: {error}");
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let listener = match TcpListener::bind(&options.addr) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Can't listen on {}: {error}", options.addr);
+            return ExitCode::FAILURE;
+        }
+    };
+    let store = Arc::new(Mutex::new(Store::open(options.data, options.size)));
+    println!("Leader board is served on http://{}", options.addr);
+
+    let connections = Arc::new(AtomicUsize::new(0));
+    for mut stream in listener.incoming().flatten() {
+        if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            connections.fetch_sub(1, Ordering::SeqCst);
+            let _ = stream.set_write_timeout(Some(TIMEOUT));
+            let _ = Response::error(503, "Server is busy").write_to(&mut stream);
+            continue;
+        }
+
+        let connection = Connection(connections.clone());
+        let store = store.clone();
+        thread::spawn(move || {
+            let _connection = connection;
+            serve(stream, &store);
+        });
+    }
+    ExitCode::SUCCESS
+}
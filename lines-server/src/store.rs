@@ -0,0 +1,359 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use lines_core::{Game, GameConfig, Preset, Replay, ReplayError};
+use serde::{Deserialize, Serialize};
+
+const STORE_VERSION: u32 = 1;
+
+// longer names are cut
+const MAX_NAME: usize = 32;
+
+/// Entry of the global table, the same fields as the entries of the game leader board
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub score: u32,
+    // hints recorded in the replay
+    pub hinted: bool,
+    // seconds since the unix epoch, the time of the server
+    pub timestamp: u64,
+    pub moves: usize,
+    pub lines: usize,
+    pub longest_line: usize,
+    pub seed: u64,
+    pub config: GameConfig,
+}
+
+/// Name of the player with the replay of the game. Everything in the entry is taken from
+/// the replay played on the server, the time of the game can't be checked, so it isn't kept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub name: String,
+    pub replay: Replay,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitError {
+    EmptyName,
+    // games with custom rules aren't ranked
+    CustomRules,
+    // games with undone moves aren't ranked, as in the game
+    Undone,
+    InvalidReplay(ReplayError),
+    // the replay doesn't end the game
+    Unfinished,
+    ScoreMismatch { sent: u32, played: u32 },
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::EmptyName => write!(f, "Name is empty"),
+            SubmitError::CustomRules => write!(f, "Games with custom rules are not ranked"),
+            SubmitError::Undone => write!(f, "Games with undone moves are not ranked"),
+            SubmitError::InvalidReplay(error) => write!(f, "Invalid replay: {error}"),
+            SubmitError::Unfinished => write!(f, "Game of the replay isn't over"),
+            SubmitError::ScoreMismatch { sent, played } => {
+                write!(f, "Score {sent} doesn't match the replay score {played}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Record {
+    entry: Entry,
+    replay: Replay,
+}
+
+/// Submitted game whose replay was played, ready for the table of its preset
+#[derive(Debug, Clone)]
+pub struct Verified {
+    preset: Preset,
+    record: Record,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    version: u32,
+    tables: BTreeMap<Preset, Vec<Record>>,
+}
+
+/// Best games of every preset with their replays, kept in the json file
+pub struct Store {
+    path: Option<PathBuf>,
+    // count of the entries kept in every table
+    size: usize,
+    tables: BTreeMap<Preset, Vec<Record>>,
+}
+
+impl Store {
+    /// Tables of the file, the unreadable file is renamed to `*.corrupt` and the tables are empty
+    pub fn open(path: PathBuf, size: usize) -> Self {
+        let mut store = Self::in_memory(size);
+
+        if let Ok(data) = fs::read_to_string(&path) {
+            match serde_json::from_str::<StoreFile>(&data) {
+                Ok(file) if file.version <= STORE_VERSION => store.tables = file.tables,
+                _ => {
+                    eprintln!("{} is corrupt, starting with empty tables", path.display());
+                    let _ = fs::rename(&path, path.with_extension("corrupt"));
+                }
+            }
+        }
+        store.path = Some(path);
+        store
+    }
+
+    pub fn in_memory(size: usize) -> Self {
+        Self {
+            path: None,
+            size,
+            tables: BTreeMap::new(),
+        }
+    }
+
+    pub fn top(&self, preset: Preset, limit: usize) -> Vec<Entry> {
+        self.tables
+            .get(&preset)
+            .map(|records| {
+                records
+                    .iter()
+                    .take(limit)
+                    .map(|record| record.entry.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Rank of the verified game from one, none if it's too low for the table.
+    /// The game sent again keeps its place
+    pub fn insert(&mut self, verified: Verified) -> Option<usize> {
+        let Verified { preset, record } = verified;
+        let records = self.tables.entry(preset).or_default();
+        let sent_again =
+            |other: &Record| other.entry.name == record.entry.name && other.replay == record.replay;
+        if let Some(index) = records.iter().position(sent_again) {
+            return Some(index + 1);
+        }
+
+        // the earlier game keeps the higher place on the same score
+        let index = records.partition_point(|other| other.entry.score >= record.entry.score);
+        if index >= self.size {
+            return None;
+        }
+        records.insert(index, record);
+        records.truncate(self.size);
+
+        self.save();
+        Some(index + 1)
+    }
+
+    // the file is replaced only by the complete new one
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let file = StoreFile {
+            version: STORE_VERSION,
+            tables: self.tables.clone(),
+        };
+        let Ok(data) = serde_json::to_string(&file) else {
+            return;
+        };
+
+        let tmp_path = path.with_extension("tmp");
+        if let Err(error) = fs::write(&tmp_path, data).and_then(|_| fs::rename(&tmp_path, path)) {
+            eprintln!("Can't write {}: {error}", path.display());
+        }
+    }
+}
+
+/// Entry of the submitted game, the store isn't needed, so the long replays are played
+/// without holding it
+pub fn verify(submission: Submission, timestamp: u64) -> Result<Verified, SubmitError> {
+    let name: String = submission.name.trim().chars().take(MAX_NAME).collect();
+    if name.is_empty() {
+        return Err(SubmitError::EmptyName);
+    }
+    let replay = submission.replay;
+    let preset = Preset::from_config(&replay.config).ok_or(SubmitError::CustomRules)?;
+    if replay.undos > 0 {
+        return Err(SubmitError::Undone);
+    }
+
+    let game = play(&replay)?;
+
+    let lines: Vec<usize> = game
+        .history()
+        .iter()
+        .flat_map(|turn| turn.cleared_shapes())
+        .flat_map(|shape| shape.lines.iter().map(Vec::len))
+        .collect();
+    let entry = Entry {
+        name,
+        score: game.score(),
+        hinted: replay.is_hinted(),
+        timestamp,
+        moves: game.history().len(),
+        lines: lines.len(),
+        longest_line: lines.into_iter().max().unwrap_or(0),
+        seed: replay.seed,
+        config: replay.config,
+    };
+    Ok(Verified {
+        preset,
+        record: Record { entry, replay },
+    })
+}
+
+// the replay is played by the rules of the game, so only the finished game with the same
+// score is accepted
+fn play(replay: &Replay) -> Result<Game, SubmitError> {
+    let game = replay.play().map_err(SubmitError::InvalidReplay)?;
+    if !game.is_over() {
+        return Err(SubmitError::Unfinished);
+    }
+    if game.score() != replay.score {
+        return Err(SubmitError::ScoreMismatch {
+            sent: replay.score,
+            played: game.score(),
+        });
+    }
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lines_core::{autoplay, Game, Greedy};
+
+    fn submission(name: &str, seed: u64, preset: Preset) -> Submission {
+        let mut game = Game::with_config(preset.config(), seed);
+        game.start();
+        autoplay(&mut game, &mut Greedy);
+        Submission {
+            name: name.to_string(),
+            replay: Replay::from_game(&game),
+        }
+    }
+
+    fn submit(
+        store: &mut Store,
+        submission: Submission,
+        timestamp: u64,
+    ) -> Result<Option<usize>, SubmitError> {
+        Ok(store.insert(verify(submission, timestamp)?))
+    }
+
+    #[test]
+    fn rank_submitted_games() {
+        let mut store = Store::in_memory(2);
+        let games: Vec<_> = (0..3)
+            .map(|seed| submission("Ann", seed, Preset::Mini))
+            .collect();
+        let mut scores: Vec<_> = games.iter().map(|game| game.replay.score).collect();
+
+        for game in &games {
+            submit(&mut store, game.clone(), 100).unwrap();
+        }
+        scores.sort_by_key(|score| !score);
+        let top = store.top(Preset::Mini, 10);
+        assert_eq!(top.len(), 2);
+        assert_eq!(
+            top.iter().map(|entry| entry.score).collect::<Vec<_>>(),
+            scores[..2]
+        );
+        assert!(top
+            .iter()
+            .all(|entry| entry.name == "Ann" && entry.moves > 0 && entry.lines > 0));
+        assert!(store.top(Preset::Classic, 10).is_empty());
+    }
+
+    #[test]
+    fn keep_game_sent_again() {
+        let mut store = Store::in_memory(10);
+        let game = submission("Ann", 7, Preset::Classic);
+
+        assert_eq!(submit(&mut store, game.clone(), 100), Ok(Some(1)));
+        assert_eq!(submit(&mut store, game.clone(), 200), Ok(Some(1)));
+        assert_eq!(store.top(Preset::Classic, 10).len(), 1);
+
+        // the same game of another player is the other entry
+        let other = Submission {
+            name: "Bob".to_string(),
+            ..game
+        };
+        assert_eq!(submit(&mut store, other, 300), Ok(Some(2)));
+    }
+
+    #[test]
+    fn reject_unranked_games() {
+        let mut store = Store::in_memory(10);
+
+        let game = submission("  ", 1, Preset::Classic);
+        assert_eq!(submit(&mut store, game, 100), Err(SubmitError::EmptyName));
+
+        let mut game = submission("Ann", 1, Preset::Classic);
+        game.replay.config.colors = 5;
+        assert_eq!(submit(&mut store, game, 100), Err(SubmitError::CustomRules));
+
+        let mut game = submission("Ann", 1, Preset::Classic);
+        game.replay.undos = 3;
+        assert_eq!(submit(&mut store, game, 100), Err(SubmitError::Undone));
+        assert!(store.top(Preset::Classic, 10).is_empty());
+    }
+
+    #[test]
+    fn flag_hinted_games() {
+        let mut store = Store::in_memory(10);
+        let mut game = submission("Ann", 2, Preset::Classic);
+        submit(&mut store, game.clone(), 100).unwrap();
+        assert!(!store.top(Preset::Classic, 1)[0].hinted);
+
+        // the hints of the replay, nothing else tells the server about them
+        game.name = "Bob".to_string();
+        game.replay.hints = 2;
+        submit(&mut store, game, 100).unwrap();
+        assert!(store.top(Preset::Classic, 2)[1].hinted);
+    }
+
+    #[test]
+    fn reject_forged_games() {
+        let mut store = Store::in_memory(10);
+        let game = submission("Ann", 3, Preset::Classic);
+
+        let mut forged = game.clone();
+        forged.replay.score += 100;
+        assert_eq!(
+            submit(&mut store, forged, 100),
+            Err(SubmitError::ScoreMismatch {
+                sent: game.replay.score + 100,
+                played: game.replay.score
+            })
+        );
+
+        let mut unfinished = game.clone();
+        unfinished.replay.moves.truncate(5);
+        assert_eq!(
+            submit(&mut store, unfinished, 100),
+            Err(SubmitError::Unfinished)
+        );
+
+        let mut invalid = game.clone();
+        let (from, _) = invalid.replay.moves[0];
+        invalid.replay.moves[0] = (from, from);
+        assert!(matches!(
+            submit(&mut store, invalid, 100),
+            Err(SubmitError::InvalidReplay(_))
+        ));
+
+        assert!(store.top(Preset::Classic, 10).is_empty());
+        assert_eq!(submit(&mut store, game, 100), Ok(Some(1)));
+    }
+}
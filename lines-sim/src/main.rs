@@ -0,0 +1,183 @@
+//! Plays seeded games by the chosen strategy without a window and writes their statistics.
+//!
+//! `lines-sim --strategy ai --games 1000 --seed 0 --preset classic --csv games.csv --json summary.json`
+//!
+//! `--scoring combo` replaces the scoring rule of the preset.
+//!
+//! Without `--json` the summary is printed to stdout.
+
+mod stats;
+
+use std::fs;
+use std::process::ExitCode;
+use std::thread;
+
+use lines_core::{Ai, Game, GameConfig, Greedy, Preset, Random, ScoringRule, Strategy};
+
+use stats::{play, to_csv, GameStats, Summary};
+
+const STRATEGIES: [&str; 3] = ["random", "greedy", "ai"];
+
+struct Options {
+    strategy: String,
+    games: usize,
+    seed: u64,
+    preset: Preset,
+    scoring: Option<ScoringRule>,
+    max_moves: usize,
+    csv: Option<String>,
+    json: Option<String>,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, String> {
+        let mut options = Self {
+            strategy: "ai".to_string(),
+            games: 100,
+            seed: 0,
+            preset: Preset::Classic,
+            scoring: None,
+            max_moves: 10_000,
+            csv: None,
+            json: None,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value of {arg}"));
+            match arg.as_str() {
+                "--strategy" => options.strategy = value()?,
+                "--games" => options.games = parse(&value()?)?,
+                "--seed" => options.seed = parse(&value()?)?,
+                "--max-moves" => options.max_moves = parse(&value()?)?,
+                "--csv" => options.csv = Some(value()?),
+                "--json" => options.json = Some(value()?),
+                "--preset" => {
+                    let name = value()?;
+                    options.preset = Preset::ALL
+                        .into_iter()
+                        .find(|preset| preset.name().eq_ignore_ascii_case(&name))
+                        .ok_or(format!("Unknown preset {name}"))?;
+                }
+                "--scoring" => {
+                    let name = value()?;
+                    options.scoring = Some(
+                        ScoringRule::ALL
+                            .into_iter()
+                            .find(|rule| rule.name().eq_ignore_ascii_case(&name))
+                            .ok_or(format!("Unknown scoring {name}"))?,
+                    );
+                }
+                _ => return Err(format!("Unknown option {arg}")),
+            }
+        }
+
+        if !STRATEGIES.contains(&options.strategy.as_str()) {
+            return Err(format!(
+                "Unknown strategy {}, expected one of {}",
+                options.strategy,
+                STRATEGIES.join(", ")
+            ));
+        }
+        Ok(options)
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("Invalid number {value}"))
+}
+
+fn strategy(name: &str, seed: u64) -> Box<dyn Strategy> {
+    match name {
+        "random" => Box::new(Random::new(seed)),
+        "greedy" => Box::new(Greedy),
+        _ => Box::new(Ai::new(seed)),
+    }
+}
+
+// games are independent, so they are split between the threads by their index; the results
+// keep the order of the runs, the seeds may wrap around
+fn simulate(options: &Options) -> Vec<GameStats> {
+    let threads = thread::available_parallelism().map_or(1, |count| count.get());
+    let config = GameConfig {
+        scoring: options.scoring.unwrap_or(options.preset.config().scoring),
+        ..options.preset.config()
+    };
+
+    let mut games: Vec<(usize, GameStats)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| {
+                scope.spawn(move || {
+                    (thread..options.games)
+                        .step_by(threads)
+                        .map(|index| {
+                            let seed = options.seed.wrapping_add(index as u64);
+                            let mut strategy = strategy(&options.strategy, seed);
+                            let game = Game::with_config(config, seed);
+                            (index, play(game, strategy.as_mut(), options.max_moves))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Simulation thread panicked"))
+            .collect()
+    });
+
+    games.sort_by_key(|(index, _)| *index);
+    games.into_iter().map(|(_, game)| game).collect()
+}
+
+fn main() -> ExitCode {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let games = simulate(&options);
+    let scoring = options.scoring.unwrap_or(options.preset.config().scoring);
+    let summary = Summary::new(&options.strategy, options.preset, scoring, &games);
+    let json = serde_json::to_string_pretty(&summary).expect("Summary is serializable");
+
+    let written = [
+        options.csv.as_ref().map(|path| (path, to_csv(&games))),
+        options.json.as_ref().map(|path| (path, json.clone())),
+    ];
+    for (path, data) in written.into_iter().flatten() {
+        if let Err(error) = fs::write(path, data) {
+            eprintln!("Can't write {path}: {error}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if options.json.is_none() {
+        println!("{json}");
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_the_order_of_wrapped_seeds() {
+        let options = Options {
+            strategy: "random".to_string(),
+            games: 4,
+            seed: u64::MAX - 1,
+            preset: Preset::Mini,
+            scoring: None,
+            max_moves: 5,
+            csv: None,
+            json: None,
+        };
+        let seeds: Vec<u64> = simulate(&options).iter().map(|game| game.seed).collect();
+        assert_eq!(seeds, [u64::MAX - 1, u64::MAX, 0, 1]);
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+
+use lines_core::{Board, Game, GameEvent, Preset, ScoringRule, Strategy};
+use serde::Serialize;
+
+// width of the score histogram buckets
+const SCORE_BUCKET: u32 = 50;
+
+/// Result of one simulated game
+#[derive(Debug, Clone, Default)]
+pub struct GameStats {
+    pub seed: u64,
+    pub score: u32,
+    pub moves: usize,
+    // count of cleared lines by their length
+    pub lines: BTreeMap<usize, usize>,
+    // the ball shared by crossing lines is counted once
+    pub cleared_balls: usize,
+    // count of cleared balls by their color
+    pub colors: BTreeMap<String, usize>,
+}
+
+impl GameStats {
+    pub fn line_count(&self) -> usize {
+        self.lines.values().sum()
+    }
+
+    pub fn longest_line(&self) -> usize {
+        self.lines.keys().max().copied().unwrap_or(0)
+    }
+}
+
+/// Play the game with the strategy until it's over. Colors of the cleared balls are
+/// taken from the copy of the board, which follows the game events like the game ui does
+pub fn play(mut game: Game, strategy: &mut dyn Strategy, max_moves: usize) -> GameStats {
+    let mut stats = GameStats {
+        seed: game.seed(),
+        ..Default::default()
+    };
+    let mut board = game.board().clone();
+    let events = game.start();
+    follow_events(&mut board, &events, &mut stats);
+
+    while !game.is_over() && stats.moves < max_moves {
+        let Some((from, to)) = strategy.choose_move(&game) else {
+            break;
+        };
+        let Ok(events) = game.make_move(from, to) else {
+            break;
+        };
+        follow_events(&mut board, &events, &mut stats);
+        stats.moves += 1;
+    }
+
+    stats.score = game.score();
+    stats
+}
+
+fn follow_events(board: &mut Board, events: &[GameEvent], stats: &mut GameStats) {
+    for event in events {
+        match event {
+            GameEvent::Moved(path) => {
+                if let (Some(from), Some(to)) = (path.first(), path.last()) {
+                    let ball = board.set(*from, None);
+                    board.set(*to, ball);
+                }
+            }
+            GameEvent::Spawned(coord, color) => {
+                board.set(*coord, Some(*color));
+            }
+            GameEvent::Cleared(shape, _) => {
+                for line in &shape.lines {
+                    *stats.lines.entry(line.len()).or_default() += 1;
+                }
+                stats.cleared_balls += shape.balls.len();
+                for coord in &shape.balls {
+                    if let Some(color) = board.set(*coord, None) {
+                        *stats.colors.entry(format!("{color:?}")).or_default() += 1;
+                    }
+                }
+            }
+            GameEvent::GameOver => (),
+        }
+    }
+}
+
+/// Distribution of the values over all games
+#[derive(Debug, Serialize)]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub p10: f64,
+    pub p90: f64,
+}
+
+impl Distribution {
+    pub fn new(values: impl Iterator<Item = f64>) -> Self {
+        let mut values: Vec<f64> = values.collect();
+        values.sort_by(f64::total_cmp);
+        if values.is_empty() {
+            values.push(0.0);
+        }
+
+        let percentile = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+        Self {
+            min: values[0],
+            max: values[values.len() - 1],
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            median: percentile(0.5),
+            p10: percentile(0.1),
+            p90: percentile(0.9),
+        }
+    }
+}
+
+/// Statistics of all simulated games
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub strategy: String,
+    pub preset: String,
+    pub scoring: String,
+    pub games: usize,
+    pub score: Distribution,
+    // count of games by the lower bound of the score bucket
+    pub score_histogram: BTreeMap<u32, usize>,
+    pub moves: Distribution,
+    pub lines_per_length: BTreeMap<usize, usize>,
+    pub colors_cleared: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    pub fn new(strategy: &str, preset: Preset, scoring: ScoringRule, games: &[GameStats]) -> Self {
+        let mut score_histogram = BTreeMap::new();
+        let mut lines_per_length = BTreeMap::new();
+        let mut colors_cleared = BTreeMap::new();
+
+        for game in games {
+            *score_histogram
+                .entry(game.score / SCORE_BUCKET * SCORE_BUCKET)
+                .or_default() += 1;
+            for (length, count) in &game.lines {
+                *lines_per_length.entry(*length).or_default() += count;
+            }
+            for (color, count) in &game.colors {
+                *colors_cleared.entry(color.clone()).or_default() += count;
+            }
+        }
+
+        Self {
+            strategy: strategy.to_string(),
+            preset: preset.name().to_string(),
+            scoring: scoring.name().to_string(),
+            games: games.len(),
+            score: Distribution::new(games.iter().map(|game| game.score as f64)),
+            score_histogram,
+            moves: Distribution::new(games.iter().map(|game| game.moves as f64)),
+            lines_per_length,
+            colors_cleared,
+        }
+    }
+}
+
+/// One row per game: the seed, the score, the length of the game and the cleared lines
+pub fn to_csv(games: &[GameStats]) -> String {
+    let mut csv = String::from("seed,score,moves,lines,cleared_balls,longest_line\n");
+    for game in games {
+        csv += &format!(
+            "{},{},{},{},{},{}\n",
+            game.seed,
+            game.score,
+            game.moves,
+            game.line_count(),
+            game.cleared_balls,
+            game.longest_line()
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(seed: u64, score: u32, moves: usize, lines: &[(usize, usize)]) -> GameStats {
+        GameStats {
+            seed,
+            score,
+            moves,
+            lines: lines.iter().copied().collect(),
+            cleared_balls: lines.iter().map(|(length, count)| length * count).sum(),
+            colors: [("Red".to_string(), score as usize / 10)].into(),
+        }
+    }
+
+    #[test]
+    fn distribution_of_known_values() {
+        // shuffled 1..=11
+        let values = [7.0, 1.0, 11.0, 4.0, 9.0, 2.0, 6.0, 10.0, 3.0, 8.0, 5.0];
+        let distribution = Distribution::new(values.into_iter());
+
+        assert_eq!(distribution.min, 1.0);
+        assert_eq!(distribution.max, 11.0);
+        assert_eq!(distribution.mean, 6.0);
+        assert_eq!(distribution.median, 6.0);
+        assert_eq!(distribution.p10, 2.0);
+        assert_eq!(distribution.p90, 10.0);
+    }
+
+    #[test]
+    fn distribution_without_values() {
+        let distribution = Distribution::new(std::iter::empty());
+        assert_eq!(distribution.min, 0.0);
+        assert_eq!(distribution.mean, 0.0);
+        assert_eq!(distribution.p90, 0.0);
+    }
+
+    #[test]
+    fn summary_adds_up_the_games() {
+        let games = [
+            game(1, 40, 30, &[(5, 2)]),
+            game(2, 120, 50, &[(5, 1), (6, 1)]),
+            game(3, 140, 70, &[(5, 3)]),
+        ];
+        let summary = Summary::new("ai", Preset::Classic, ScoringRule::Standard, &games);
+
+        assert_eq!(summary.games, 3);
+        assert_eq!(summary.score.mean, 100.0);
+        assert_eq!(summary.score.median, 120.0);
+        assert_eq!(summary.moves.median, 50.0);
+        assert_eq!(summary.score_histogram, [(0, 1), (100, 2)].into());
+        assert_eq!(summary.lines_per_length, [(5, 6), (6, 1)].into());
+        assert_eq!(summary.colors_cleared, [("Red".to_string(), 30)].into());
+    }
+
+    #[test]
+    fn csv_row_per_game() {
+        let csv = to_csv(&[game(7, 120, 50, &[(5, 1), (6, 1)])]);
+        assert_eq!(
+            csv,
+            "seed,score,moves,lines,cleared_balls,longest_line\n7,120,50,2,11,6\n"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+// launch options: `--name value` or `--name=value` on desktop, `?name=value` in the page url
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get(name: &str) -> Option<String> {
+    let key = format!("--{name}");
+    let mut args = std::env::args().skip(1).peekable();
+
+    while let Some(arg) = args.next() {
+        if arg == key {
+            // flag without value
+            let value = args.next_if(|value| !value.starts_with("--"));
+            return Some(value.unwrap_or_default());
+        }
+        if let Some(value) = arg.strip_prefix(&key).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn get(name: &str) -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|param| match param.split_once('=') {
+            Some((key, value)) if key == name => Some(value.to_string()),
+            None if param == name => Some(String::new()),
+            _ => None,
+        })
+}
+
+pub fn parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    get(name)?.parse().ok()
+}
+
+pub fn has(name: &str) -> bool {
+    get(name).is_some()
+}
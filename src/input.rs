@@ -0,0 +1,68 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+// actions of the keyboard and gamepads, the mouse is handled by the ui buttons and the board
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Cancel,
+    // move the focus between the board and the footer menu
+    SwitchFocus,
+}
+
+impl Action {
+    fn keys(&self) -> &'static [KeyCode] {
+        match self {
+            Action::Up => &[KeyCode::Up, KeyCode::W],
+            Action::Down => &[KeyCode::Down, KeyCode::S],
+            Action::Left => &[KeyCode::Left, KeyCode::A],
+            Action::Right => &[KeyCode::Right, KeyCode::D],
+            Action::Confirm => &[KeyCode::Return, KeyCode::NumpadEnter, KeyCode::Space],
+            Action::Cancel => &[KeyCode::Escape, KeyCode::Back],
+            Action::SwitchFocus => &[KeyCode::Tab],
+        }
+    }
+
+    fn buttons(&self) -> &'static [GamepadButtonType] {
+        match self {
+            Action::Up => &[GamepadButtonType::DPadUp],
+            Action::Down => &[GamepadButtonType::DPadDown],
+            Action::Left => &[GamepadButtonType::DPadLeft],
+            Action::Right => &[GamepadButtonType::DPadRight],
+            Action::Confirm => &[GamepadButtonType::South],
+            Action::Cancel => &[GamepadButtonType::East],
+            Action::SwitchFocus => &[GamepadButtonType::Select],
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    keys: Res<'w, Input<KeyCode>>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    gamepads: Res<'w, Gamepads>,
+}
+
+impl Actions<'_> {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        // keep ctrl+z and other shortcuts for the history
+        let modifiers = self.keys.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]);
+
+        (!modifiers && self.keys.any_just_pressed(action.keys().iter().copied()))
+            || self.gamepads.iter().any(|gamepad| {
+                action.buttons().iter().any(|button| {
+                    self.buttons
+                        .just_pressed(GamepadButton::new(gamepad, *button))
+                })
+            })
+    }
+}
@@ -1,17 +1,28 @@
 // disable console on windows for release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+// bevy systems usually have complex queries and many arguments
+#![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
+mod args;
+mod input;
 mod plugins;
+mod storage;
 
 use bevy::prelude::*;
 use bevy_embedded_assets::EmbeddedAssetPlugin;
 use bevy_simple_text_input::TextInputPlugin;
 
+use plugins::autoplay::AutoplayPlugin;
 use plugins::board::BoardPlugin;
 use plugins::game_score::GameScorePlugin;
 use plugins::layout::LayoutPlugin;
 use plugins::leader_board::LeaderBoardPlugin;
+use plugins::leader_board_file::LeaderBoardFilePlugin;
 use plugins::menu::MenuPlugin;
+use plugins::new_game::NewGamePlugin;
+use plugins::replay::ReplayPlugin;
+use plugins::save::SavePlugin;
+use plugins::seed::SeedPlugin;
 
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum GameState {
@@ -19,6 +30,27 @@ pub enum GameState {
     Playing,
     GameOver,
     Restarting,
+    Replaying,
+}
+
+// phase of the turn shown on the board, input is accepted only in idle and selecting phases
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum TurnPhase {
+    #[default]
+    Idle,
+    Selecting,
+    Moving,
+    Clearing,
+    Spawning,
+    CheckingGameOver,
+}
+
+// receiver of the keyboard and gamepad input
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum InputFocus {
+    #[default]
+    Board,
+    Menu,
 }
 
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
@@ -29,14 +61,29 @@ pub enum LeaderBoardState {
     InputName,
 }
 
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum ResumeState {
+    #[default]
+    Hide,
+    Show,
+}
+
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum NewGameState {
+    #[default]
+    Hide,
+    Show,
+}
+
 pub struct GameOptions;
 impl GameOptions {
     pub const TILE_SIZE: f32 = 45.0;
     pub const TILE_PADDING: f32 = 2.5;
-    pub const TILE_COUNT: u8 = 9;
-    pub const BOARD_SIZE: f32 = GameOptions::TILE_SIZE * GameOptions::TILE_COUNT as f32;
+    // size of the classic 9x9 board, width of the overlays over the board
+    pub const BOARD_SIZE: f32 = GameOptions::TILE_SIZE * 9.0;
+    // the largest board which fits into the main area of the window
+    pub const MAX_TILE_COUNT: u8 = 10;
     pub const BALL_SIZE: f32 = 35.0;
-    pub const MIN_BALLS_ON_LINE: usize = 5;
     pub const WINDOW_WIDTH: f32 = 900.;
     pub const WINDOW_HEIGHT: f32 = 600.;
 
@@ -45,9 +92,19 @@ impl GameOptions {
 }
 
 fn main() {
+    #[cfg(not(target_arch = "wasm32"))]
+    if args::has("headless") {
+        plugins::autoplay::play_headless();
+        return;
+    }
+
     let mut app = App::new();
     app.add_state::<GameState>()
+        .add_state::<TurnPhase>()
+        .add_state::<InputFocus>()
         .add_state::<LeaderBoardState>()
+        .add_state::<ResumeState>()
+        .add_state::<NewGameState>()
         .add_plugins(
             DefaultPlugins
                 .set(WindowPlugin {
@@ -75,8 +132,14 @@ fn main() {
         LayoutPlugin,
         BoardPlugin,
         LeaderBoardPlugin,
+        LeaderBoardFilePlugin,
         GameScorePlugin,
         MenuPlugin,
+        NewGamePlugin,
+        SeedPlugin,
+        SavePlugin,
+        ReplayPlugin,
+        AutoplayPlugin,
     ));
 
     app.add_systems(Startup, spawn_camera).run();
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use lines_core::{Ai, Coordinates, Strategy};
+
+use super::board::board::Board;
+use super::board::{accepts_input, BoardInputEvent};
+#[cfg(not(target_arch = "wasm32"))]
+use super::new_game::config_from_args;
+use crate::args;
+use crate::GameState;
+use crate::LeaderBoardState;
+use crate::NewGameState;
+use crate::ResumeState;
+
+// delay between the selection of the ball and its move
+const STEP_DURATION: f32 = 0.4;
+// the finished game is shown before the next one starts
+const RESTART_DELAY: f32 = 5.0;
+
+pub struct AutoplayPlugin;
+
+impl Plugin for AutoplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Autoplay>()
+            .add_systems(OnEnter(GameState::Playing), reset_autoplay)
+            .add_systems(
+                Update,
+                (
+                    player_input_system,
+                    attract_mode_system
+                        .run_if(in_state(GameState::Playing))
+                        .run_if(in_state(LeaderBoardState::Hide))
+                        .run_if(in_state(ResumeState::Hide))
+                        .run_if(in_state(NewGameState::Hide)),
+                    autoplay_system
+                        .run_if(in_state(GameState::Playing))
+                        .run_if(in_state(ResumeState::Hide))
+                        .run_if(in_state(NewGameState::Hide))
+                        .run_if(accepts_input),
+                    restart_system.run_if(in_state(GameState::GameOver)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// `--autoplay` lets the ai play the game on screen, `--attract 30` starts it after 30 seconds
+// without input on the new game. Any input gives the control back with a new game
+#[derive(Resource)]
+pub struct Autoplay {
+    pub active: bool,
+    // the current game has moves of the ai, it isn't ranked or saved
+    pub played: bool,
+    ai: Ai,
+    // target tile of the ball selected by the ai
+    target: Option<Coordinates>,
+    step: Timer,
+    restart: Timer,
+    idle: Option<Timer>,
+}
+
+impl Default for Autoplay {
+    fn default() -> Self {
+        Self {
+            active: args::has("autoplay"),
+            played: false,
+            ai: Ai::new(rand::random()),
+            target: None,
+            step: Timer::from_seconds(STEP_DURATION, TimerMode::Repeating),
+            restart: Timer::from_seconds(RESTART_DELAY, TimerMode::Once),
+            idle: idle_timer(),
+        }
+    }
+}
+
+// `--attract` takes the positive number of seconds, the attract mode is off for the other values
+fn idle_timer() -> Option<Timer> {
+    let seconds: f32 = args::parse("attract")?;
+    if seconds > 0.0 && seconds.is_finite() {
+        Some(Timer::from_seconds(seconds, TimerMode::Once))
+    } else {
+        warn!("Attract mode is off: {seconds} isn't a positive number of seconds");
+        None
+    }
+}
+
+// `--headless` plays the game by the ai without a window and prints the result,
+// the rules are chosen by `--preset` and `--scoring` as for the game on screen
+#[cfg(not(target_arch = "wasm32"))]
+pub fn play_headless() {
+    let seed = args::parse("seed").unwrap_or_else(rand::random);
+    let mut game = lines_core::Game::with_config(config_from_args(), seed);
+    game.start();
+
+    let mut ai = Ai::new(seed);
+    let moves = lines_core::autoplay(&mut game, &mut ai);
+    println!(
+        "seed {seed}: {} points in {moves} moves by {}",
+        game.score(),
+        ai.name()
+    );
+}
+
+fn reset_autoplay(mut autoplay: ResMut<Autoplay>) {
+    autoplay.played = false;
+    autoplay.target = None;
+    autoplay.restart.reset();
+    if let Some(idle) = &mut autoplay.idle {
+        idle.reset();
+    }
+}
+
+fn player_input_system(
+    mouse_input: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    touches: Res<Touches>,
+    mut autoplay: ResMut<Autoplay>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_leaders_state: ResMut<NextState<LeaderBoardState>>,
+) {
+    let pressed = mouse_input.get_just_pressed().len() > 0
+        || keys.get_just_pressed().len() > 0
+        || buttons.get_just_pressed().len() > 0
+        || touches.any_just_pressed();
+    if !pressed {
+        return;
+    }
+
+    if let Some(idle) = &mut autoplay.idle {
+        idle.reset();
+    }
+    if autoplay.active {
+        autoplay.active = false;
+        // the player doesn't continue the game of the ai
+        next_game_state.set(GameState::Restarting);
+        next_leaders_state.set(LeaderBoardState::Hide);
+    }
+}
+
+// the ai plays the new game nobody touched for a while
+fn attract_mode_system(time: Res<Time>, board: Res<Board>, mut autoplay: ResMut<Autoplay>) {
+    if autoplay.active || !board.game.history().is_empty() {
+        return;
+    }
+    let Some(idle) = &mut autoplay.idle else {
+        return;
+    };
+    if idle.tick(time.delta()).just_finished() {
+        autoplay.active = true;
+    }
+}
+
+// select the ball chosen by the ai, then move it on the next step
+fn autoplay_system(
+    time: Res<Time>,
+    board: Res<Board>,
+    mut autoplay: ResMut<Autoplay>,
+    mut ev_input: EventWriter<BoardInputEvent>,
+) {
+    if !autoplay.active || !autoplay.step.tick(time.delta()).just_finished() {
+        return;
+    }
+    autoplay.played = true;
+
+    match (board.active_ball, autoplay.target.take()) {
+        (Some(_), Some(target)) => ev_input.send(BoardInputEvent::Click(target)),
+        _ => {
+            if let Some((from, to)) = autoplay.ai.choose_move(&board.game) {
+                autoplay.target = Some(to);
+                ev_input.send(BoardInputEvent::Click(from));
+            }
+        }
+    }
+}
+
+// demo games follow each other
+fn restart_system(
+    time: Res<Time>,
+    mut autoplay: ResMut<Autoplay>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_leaders_state: ResMut<NextState<LeaderBoardState>>,
+) {
+    if autoplay.active && autoplay.restart.tick(time.delta()).just_finished() {
+        next_game_state.set(GameState::Restarting);
+        next_leaders_state.set(LeaderBoardState::Hide);
+    }
+}
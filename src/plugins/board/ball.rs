@@ -1,60 +1,22 @@
 use bevy::prelude::*;
-use rand::prelude::*;
 
-#[derive(Debug, Component)]
-pub struct Ball;
+pub use lines_core::BallColor;
 
 #[derive(Debug, Component)]
-pub struct BallEntity {
-    pub color: BallColor,
-    pub entity: Entity,
-}
-
-impl BallEntity {
-    pub fn new(color: BallColor, entity: Entity) -> Self {
-        Self { color, entity }
-    }
-}
-
-#[derive(Debug, Component, Clone, Copy, PartialEq, Eq)]
-pub enum BallColor {
-    Red,
-    Blue,
-    Cyan,
-    Green,
-    Purple,
-    Brown,
-    Yellow,
-}
-
-impl BallColor {
-    pub fn new() -> Self {
-        let mut rng = rand::thread_rng();
-        match rng.gen_range(0..7) {
-            0 => BallColor::Red,
-            1 => BallColor::Blue,
-            2 => BallColor::Cyan,
-            3 => BallColor::Green,
-            4 => BallColor::Purple,
-            5 => BallColor::Brown,
-            _ => BallColor::Yellow,
-        }
-    }
-}
+pub struct Ball;
 
-impl From<BallColor> for Color {
-    fn from(color: BallColor) -> Self {
-        let hex = match color {
-            BallColor::Red => "ec1c24",
-            BallColor::Blue => "0e1bd2",
-            BallColor::Cyan => "00a8f3",
-            BallColor::Green => "069a30",
-            BallColor::Purple => "d71fda",
-            BallColor::Brown => "b97a56",
-            BallColor::Yellow => "fff200",
-        };
-        Color::hex(hex).unwrap()
-    }
+pub fn ui_color(color: BallColor) -> Color {
+    let hex = match color {
+        BallColor::Red => "ec1c24",
+        BallColor::Blue => "0e1bd2",
+        BallColor::Cyan => "00a8f3",
+        BallColor::Green => "069a30",
+        BallColor::Purple => "d71fda",
+        BallColor::Brown => "b97a56",
+        BallColor::Yellow => "fff200",
+        BallColor::Orange => "ff7f27",
+    };
+    Color::hex(hex).unwrap()
 }
 
 #[derive(Resource)]
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use super::board::Board;
+use super::events::BoardInputEvent;
+use super::preview::PathPreview;
+use super::BoardTile;
+use super::Coordinates;
+use super::GameOptions;
+use crate::input::{Action, Actions};
+
+const CURSOR_COLOR: Color = Color::YELLOW_GREEN;
+
+// tile cursor moved by the keyboard and gamepad, hidden until they are used
+#[derive(Resource)]
+pub struct BoardCursor {
+    pub position: Coordinates,
+    pub visible: bool,
+}
+
+impl Default for BoardCursor {
+    fn default() -> Self {
+        Self {
+            position: Coordinates(0, 0),
+            visible: false,
+        }
+    }
+}
+
+pub fn cursor_input_system(
+    actions: Actions,
+    board: Res<Board>,
+    mut cursor: ResMut<BoardCursor>,
+    mut preview: ResMut<PathPreview>,
+    mut ev_input: EventWriter<BoardInputEvent>,
+) {
+    let (width, height) = (board.game.board().width(), board.game.board().height());
+    let Coordinates(x, y) = cursor.position;
+
+    let position = if actions.just_pressed(Action::Up) {
+        Coordinates(x, y.saturating_sub(1))
+    } else if actions.just_pressed(Action::Down) {
+        Coordinates(x, (y + 1).min(height - 1))
+    } else if actions.just_pressed(Action::Left) {
+        Coordinates(x.saturating_sub(1), y)
+    } else if actions.just_pressed(Action::Right) {
+        Coordinates((x + 1).min(width - 1), y)
+    } else {
+        // keep the cursor on the board after the size change
+        Coordinates(x.min(width - 1), y.min(height - 1))
+    };
+
+    if actions.just_pressed(Action::Confirm) && cursor.visible {
+        ev_input.send(BoardInputEvent::Click(position));
+    }
+    if actions.just_pressed(Action::Cancel) {
+        ev_input.send(BoardInputEvent::Cancel);
+    }
+
+    let pressed = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Confirm,
+    ]
+    .into_iter()
+    .any(|action| actions.just_pressed(action));
+
+    if cursor.position != position || (pressed && !cursor.visible) {
+        cursor.position = position;
+        cursor.visible = true;
+        preview.target = Some(position);
+    }
+}
+
+// the mouse doesn't need the cursor
+pub fn hide_cursor_system(mouse_input: Res<Input<MouseButton>>, mut cursor: ResMut<BoardCursor>) {
+    if mouse_input.just_pressed(MouseButton::Left) && cursor.visible {
+        cursor.visible = false;
+    }
+}
+
+pub fn render_cursor(
+    cursor: Res<BoardCursor>,
+    mut q_tiles: Query<(&Coordinates, &mut BorderColor), With<BoardTile>>,
+    q_added: Query<(), Added<BoardTile>>,
+) {
+    if !cursor.is_changed() && q_added.is_empty() {
+        return;
+    }
+    for (coord, mut border) in q_tiles.iter_mut() {
+        border.0 = if cursor.visible && *coord == cursor.position {
+            CURSOR_COLOR
+        } else {
+            GameOptions::BOARD_COLOR
+        };
+    }
+}
@@ -1,8 +1,29 @@
-use super::ball::BallColor;
-use bevy::prelude::Event;
+use bevy::prelude::{Deref, Event};
+use lines_core::{Coordinates, GameEvent};
 
+// change of the game rules state, which should be shown on the board
+#[derive(Event, Deref)]
+pub struct BoardEvent(pub GameEvent);
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryEvent {
+    Undo,
+    Redo,
+}
+
+// rebuild balls on the board from the game rules state
 #[derive(Event)]
-pub struct SpawnNewBallEvent(pub BallColor);
+pub struct RefreshBoardEvent;
+
+// input on the board tiles from the mouse, keyboard or gamepad
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardInputEvent {
+    // select the ball or move the selected ball to the tile
+    Click(Coordinates),
+    // unselect the selected ball
+    Cancel,
+}
 
+// show the best move of the current board
 #[derive(Event)]
-pub struct ChangeNextBallsEvent;
+pub struct HintEvent;
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use super::board::Board;
+use super::events::*;
+use super::preview::PathPreview;
+use crate::args;
+
+// `--hint-limit 3` sets count of hints per game, `--ranked` disables hints.
+// Games with hints are flagged on the leader board
+#[derive(Resource)]
+pub struct HintSettings {
+    pub limit: Option<u32>,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        Self {
+            limit: if args::has("ranked") {
+                Some(0)
+            } else {
+                args::parse("hint-limit")
+            },
+        }
+    }
+}
+
+impl HintSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.limit != Some(0)
+    }
+}
+
+// the hint is shown until the board is changed
+pub fn clear_hint(
+    mut preview: ResMut<PathPreview>,
+    mut ev_board: EventReader<BoardEvent>,
+    mut ev_refresh: EventReader<RefreshBoardEvent>,
+) {
+    let changed = ev_board.iter().count() + ev_refresh.iter().count() > 0;
+    if changed && preview.hint.is_some() {
+        preview.hint = None;
+    }
+}
+
+pub fn handle_hint_events(
+    mut board: ResMut<Board>,
+    mut preview: ResMut<PathPreview>,
+    mut ev_hint: EventReader<HintEvent>,
+) {
+    if ev_hint.iter().count() == 0 {
+        return;
+    }
+    if let Some(hint) = board.game.hint() {
+        preview.hint = Some((hint.from, hint.to));
+    }
+}
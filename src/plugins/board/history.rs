@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use super::board::Board;
+use super::events::*;
+use crate::args;
+
+const DEFAULT_UNDO_LIMIT: usize = 3;
+
+// `--undo-limit 5` sets count of moves which can be undone, `--ranked` disables undo.
+// Games with undone moves don't get to the leader board
+#[derive(Resource)]
+pub struct UndoSettings {
+    pub limit: usize,
+    pub ranked: bool,
+}
+
+impl Default for UndoSettings {
+    fn default() -> Self {
+        let ranked = args::has("ranked");
+        Self {
+            limit: if ranked {
+                0
+            } else {
+                args::parse("undo-limit").unwrap_or(DEFAULT_UNDO_LIMIT)
+            },
+            ranked,
+        }
+    }
+}
+
+impl UndoSettings {
+    pub fn is_enabled(&self) -> bool {
+        !self.ranked && self.limit > 0
+    }
+}
+
+// ctrl+z to undo, ctrl+shift+z or ctrl+y to redo
+pub fn history_keyboard_system(
+    keys: Res<Input<KeyCode>>,
+    mut ev_history: EventWriter<HistoryEvent>,
+) {
+    let ctrl = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if !ctrl {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Z) {
+        ev_history.send(if shift {
+            HistoryEvent::Redo
+        } else {
+            HistoryEvent::Undo
+        });
+    } else if keys.just_pressed(KeyCode::Y) {
+        ev_history.send(HistoryEvent::Redo);
+    }
+}
+
+pub fn handle_history_events(
+    settings: Res<UndoSettings>,
+    mut board: ResMut<Board>,
+    mut ev_history: EventReader<HistoryEvent>,
+    mut ev_refresh: EventWriter<RefreshBoardEvent>,
+) {
+    for event in ev_history.iter() {
+        if !settings.is_enabled() {
+            continue;
+        }
+        let changed = match event {
+            HistoryEvent::Undo => board.game.undo().is_some(),
+            HistoryEvent::Redo => board.game.redo().is_some(),
+        };
+        if changed {
+            ev_refresh.send(RefreshBoardEvent);
+        }
+    }
+}
@@ -1,11 +1,21 @@
-pub use crate::plugins::game_score::IncrementCurrentGameScore;
+pub use crate::plugins::game_score::{GameScore, IncrementCurrentGameScore};
 pub use crate::plugins::layout;
+pub use crate::plugins::save;
+pub use crate::plugins::seed;
 
 mod ball;
+#[allow(clippy::module_inception)]
 pub mod board;
+mod cursor;
 mod events;
+mod hint;
+mod history;
+mod movement;
 mod next_balls;
+mod preview;
 mod systems;
+mod touch;
+mod turn;
 
 use bevy::prelude::*;
 
@@ -14,72 +24,111 @@ use board::Board;
 
 use next_balls::NextBallsPlugin;
 
-use events::*;
+use cursor::*;
+use hint::*;
+use history::*;
+use movement::*;
+use preview::*;
 use systems::*;
+use touch::*;
+use turn::*;
+
+pub use events::{BoardEvent, BoardInputEvent, HintEvent, HistoryEvent, RefreshBoardEvent};
+pub use hint::HintSettings;
+pub use history::UndoSettings;
+pub use systems::spawn_startup_balls;
+pub use turn::accepts_input;
 
 pub use crate::GameOptions;
 pub use crate::GameState;
+pub use crate::InputFocus;
+pub use crate::LeaderBoardState;
+pub use crate::NewGameState;
+pub use crate::ResumeState;
+pub use crate::TurnPhase;
 
 pub struct BoardPlugin;
 
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<BallAssets>().init_resource::<Board>();
+        app.init_resource::<BallAssets>()
+            .init_resource::<Board>()
+            .init_resource::<UndoSettings>()
+            .init_resource::<HintSettings>()
+            .init_resource::<MoveSettings>()
+            .init_resource::<BoardCursor>()
+            .init_resource::<PathPreview>()
+            .init_resource::<TouchDrag>();
 
         app.add_plugins(NextBallsPlugin);
 
-        app.add_event::<SpawnNewBallEvent>();
-        app.add_event::<ChangeNextBallsEvent>();
+        app.add_event::<BoardEvent>()
+            .add_event::<HistoryEvent>()
+            .add_event::<HintEvent>()
+            .add_event::<BoardInputEvent>()
+            .add_event::<RefreshBoardEvent>();
 
-        app.add_systems(Startup, spawn_board)
-            .add_systems(
-                OnEnter(GameState::Playing),
-                (spawn_startup_balls, spawn_animation_timer),
-            )
-            .add_systems(
-                Update,
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            (spawn_startup_balls, spawn_animation_timer),
+        )
+        .add_systems(OnEnter(TurnPhase::CheckingGameOver), check_game_over)
+        .add_systems(
+            Update,
+            (
+                animate_ball_system,
                 (
-                    render_balls,
-                    animate_ball_system,
-                    spawn_new_ball,
-                    handle_mouse_clicks,
-                ),
-            )
-            .add_systems(OnExit(GameState::Playing), despawn_board_balls);
-    }
-}
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Component)]
-pub struct Coordinates(pub u8, pub u8);
-
-impl TryFrom<Vec2> for Coordinates {
-    type Error = &'static str;
-    fn try_from(pos: Vec2) -> Result<Self, Self::Error> {
-        let window_size = Vec2::new(GameOptions::WINDOW_WIDTH, GameOptions::WINDOW_HEIGHT);
-        let position = pos - window_size / 2.;
-        let size = GameOptions::BOARD_SIZE / 2.;
-
-        if size < position.x.abs() || size < position.y.abs() {
-            return Err("Cursor position out of bounds");
-        }
-
-        let coord = Coordinates(
-            ((position.x + size) / GameOptions::TILE_SIZE) as u8,
-            ((position.y + size) / GameOptions::TILE_SIZE) as u8,
-        );
-        Ok(coord)
-    }
-}
-
-impl From<Coordinates> for Vec2 {
-    fn from(coord: Coordinates) -> Self {
-        let offset = -GameOptions::BOARD_SIZE / 2.;
-        Vec2::new(
-            (coord.0 as f32 * GameOptions::TILE_SIZE) + (GameOptions::TILE_SIZE / 2.) + offset,
-            -((coord.1 as f32 * GameOptions::TILE_SIZE) + (GameOptions::TILE_SIZE / 2.) + offset),
+                    (
+                        handle_mouse_clicks,
+                        touch_drag_system,
+                        cursor_input_system
+                            .run_if(in_state(LeaderBoardState::Hide))
+                            .run_if(in_state(InputFocus::Board)),
+                    )
+                        .run_if(in_state(GameState::Playing))
+                        .run_if(in_state(ResumeState::Hide))
+                        .run_if(in_state(NewGameState::Hide)),
+                    handle_board_input
+                        .run_if(in_state(GameState::Playing))
+                        .run_if(accepts_input),
+                    queue_board_events,
+                    (move_ball_system, animate_clear_system, animate_spawn_system),
+                    advance_turn_phase,
+                )
+                    .chain(),
+                animate_score_popup_system,
+                (hide_cursor_system, render_cursor).chain(),
+                (
+                    hover_preview_system
+                        .run_if(in_state(GameState::Playing))
+                        .run_if(in_state(ResumeState::Hide))
+                        .run_if(in_state(NewGameState::Hide)),
+                    render_path_preview,
+                )
+                    .chain(),
+                (history_keyboard_system, handle_history_events)
+                    .chain()
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(accepts_input),
+                (clear_hint, handle_hint_events.run_if(accepts_input))
+                    .chain()
+                    .run_if(in_state(GameState::Playing))
+                    .after(refresh_board),
+                refresh_board
+                    .after(handle_history_events)
+                    .before(queue_board_events),
+            ),
         )
+        .add_systems(OnExit(GameState::Playing), despawn_board_balls)
+        .add_systems(OnExit(GameState::Replaying), despawn_board_balls);
     }
 }
 
+pub use lines_core::Coordinates;
+
 #[derive(Component)]
 pub struct BoardTile;
+
+// grid node of the board tiles
+#[derive(Component)]
+pub struct BoardGrid;
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+use super::board::Board;
+use super::Coordinates;
+use crate::args;
+
+const DEFAULT_MOVE_SPEED: f32 = 20.0;
+
+// `--move-speed 10` sets tiles per second of the moving ball, 0 moves it instantly
+#[derive(Resource)]
+pub struct MoveSettings {
+    pub speed: f32,
+}
+
+impl Default for MoveSettings {
+    fn default() -> Self {
+        Self {
+            speed: args::parse("move-speed").unwrap_or(DEFAULT_MOVE_SPEED),
+        }
+    }
+}
+
+// path of the ball moving tile by tile to the target tile
+#[derive(Component)]
+pub struct BallPath {
+    path: Vec<Coordinates>,
+    step: usize,
+    timer: Timer,
+}
+
+impl BallPath {
+    pub fn new(path: Vec<Coordinates>, speed: f32) -> Self {
+        Self {
+            path,
+            step: 0,
+            timer: Timer::from_seconds(1.0 / speed, TimerMode::Repeating),
+        }
+    }
+}
+
+pub fn move_ball_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    board: Res<Board>,
+    mut q_balls: Query<(Entity, &mut BallPath, &mut Coordinates)>,
+) {
+    for (ball, mut ball_path, mut coordinates) in q_balls.iter_mut() {
+        let steps = ball_path
+            .timer
+            .tick(time.delta())
+            .times_finished_this_tick() as usize;
+        if steps == 0 {
+            continue;
+        }
+
+        let last = ball_path.path.len() - 1;
+        ball_path.step = (ball_path.step + steps).min(last);
+        let coord = ball_path.path[ball_path.step];
+
+        if let Some(tile) = board.tiles.get(&coord) {
+            commands.entity(ball).set_parent(*tile);
+        }
+        *coordinates = coord;
+
+        if ball_path.step == last {
+            commands.entity(ball).remove::<BallPath>();
+        }
+    }
+}
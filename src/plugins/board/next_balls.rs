@@ -1,8 +1,7 @@
 use bevy::prelude::*;
 
-use super::ball::BallAssets;
-use super::ball::BallColor;
-use super::events::ChangeNextBallsEvent;
+use super::ball::{ui_color, BallAssets, BallColor};
+use super::board::Board;
 use super::layout::HeaderCenter;
 use super::GameOptions;
 use super::GameState;
@@ -11,99 +10,99 @@ pub struct NextBallsPlugin;
 
 impl Plugin for NextBallsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_next_board)
-            .add_systems(OnEnter(GameState::Playing), spawn_next_balls)
-            .add_systems(Update, (render_next_color, change_next_color))
-            .add_systems(OnExit(GameState::Playing), despawn_next_balls);
+        app.add_systems(
+            Update,
+            render_next_balls
+                .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Replaying))),
+        )
+        .add_systems(OnExit(GameState::Playing), despawn_next_balls)
+        .add_systems(OnExit(GameState::Replaying), despawn_next_balls);
     }
 }
 
 #[derive(Component)]
 struct NextTile;
 
+// index of the ball in the next balls of the game
 #[derive(Debug, Component)]
-pub struct NextBall {
-    pub color: BallColor,
-}
-
-impl NextBall {
-    fn new() -> Self {
-        Self {
-            color: BallColor::new(),
-        }
-    }
-    fn change_color(&mut self) {
-        self.color = BallColor::new()
-    }
-}
-
-fn spawn_next_board(mut commands: Commands, header: Query<Entity, With<HeaderCenter>>) {
-    let header = header.get_single().expect("Header not found");
+pub struct NextBall(usize);
 
+// one tile for every ball spawned per turn of the game config
+fn spawn_next_tiles(
+    commands: &mut Commands,
+    ball_assets: &BallAssets,
+    header: Entity,
+    colors: &[BallColor],
+) {
     commands.entity(header).with_children(|header| {
-        for _ in 0..3 {
-            header.spawn((
-                NodeBundle {
-                    style: Style {
-                        width: Val::Px(GameOptions::TILE_SIZE),
-                        height: Val::Px(GameOptions::TILE_SIZE),
-                        border: UiRect::all(Val::Px(GameOptions::TILE_PADDING)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
+        for (index, color) in colors.iter().enumerate() {
+            header
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(GameOptions::TILE_SIZE),
+                            height: Val::Px(GameOptions::TILE_SIZE),
+                            border: UiRect::all(Val::Px(GameOptions::TILE_PADDING)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        border_color: GameOptions::BOARD_COLOR.into(),
+                        background_color: GameOptions::TILE_COLOR.into(),
                         ..default()
                     },
-                    border_color: GameOptions::BOARD_COLOR.into(),
-                    background_color: GameOptions::TILE_COLOR.into(),
-                    ..default()
-                },
-                NextTile,
-            ));
+                    NextTile,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        ImageBundle {
+                            style: Style {
+                                width: Val::Px(GameOptions::BALL_SIZE),
+                                height: Val::Px(GameOptions::BALL_SIZE),
+                                ..default()
+                            },
+                            background_color: ui_color(*color).into(),
+                            image: UiImage::new(ball_assets.texture.clone()),
+                            ..default()
+                        },
+                        NextBall(index),
+                    ));
+                });
         }
     });
 }
 
-fn spawn_next_balls(
+fn render_next_balls(
+    board: Res<Board>,
     ball_assets: Res<BallAssets>,
     mut commands: Commands,
+    q_header: Query<Entity, With<HeaderCenter>>,
     q_next_tiles: Query<Entity, With<NextTile>>,
+    mut q_next_ball: Query<(&NextBall, &mut BackgroundColor)>,
 ) {
-    for entity in q_next_tiles.iter() {
-        commands.entity(entity).with_children(|parent| {
-            parent.spawn((
-                ImageBundle {
-                    style: Style {
-                        width: Val::Px(GameOptions::BALL_SIZE),
-                        height: Val::Px(GameOptions::BALL_SIZE),
-                        ..default()
-                    },
-                    image: UiImage::new(ball_assets.texture.clone()),
-                    ..default()
-                },
-                NextBall::new(),
-            ));
-        });
-    }
-}
+    let next_balls = board.game.next_balls();
 
-fn render_next_color(
-    mut query_next_ball: Query<(&NextBall, &mut BackgroundColor), Changed<NextBall>>,
-) {
-    for (ball, mut color) in query_next_ball.iter_mut() {
-        color.0 = ball.color.into();
+    // spawn the tiles again when the count of balls per turn was changed
+    if q_next_ball.iter().count() != next_balls.len() {
+        for entity in q_next_tiles.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        let header = q_header.get_single().expect("Header not found");
+        spawn_next_tiles(&mut commands, &ball_assets, header, next_balls);
+        return;
     }
-}
 
-fn change_next_color(
-    mut query_next_ball: Query<&mut NextBall, With<NextBall>>,
-    mut ev_change_next: EventReader<ChangeNextBallsEvent>,
-) {
-    for _ in ev_change_next.iter() {
-        for mut ball in query_next_ball.iter_mut() {
-            ball.change_color();
+    if !board.is_changed() {
+        return;
+    }
+    for (ball, mut color) in q_next_ball.iter_mut() {
+        if let Some(next_color) = next_balls.get(ball.0) {
+            color.0 = ui_color(*next_color);
         }
     }
 }
 
+// empty tiles are left in the header after the game
 fn despawn_next_balls(mut commands: Commands, q_next_ball: Query<Entity, With<NextBall>>) {
     for entity in q_next_ball.iter() {
         commands.entity(entity).despawn_recursive();
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use super::board::Board;
+use super::BoardTile;
+use super::Coordinates;
+use super::GameOptions;
+
+const PATH_COLOR: Color = Color::rgb(0.75, 0.88, 0.62);
+const UNREACHABLE_COLOR: Color = Color::rgb(0.92, 0.7, 0.7);
+const HINT_COLOR: Color = Color::rgb(0.7, 0.8, 0.95);
+
+// empty tile the selected ball is dragged or pointed to
+#[derive(Resource, Default)]
+pub struct PathPreview {
+    pub target: Option<Coordinates>,
+    // source and destination tiles of the suggested move
+    pub hint: Option<(Coordinates, Coordinates)>,
+}
+
+// tile under the mouse is the target of the preview
+pub fn hover_preview_system(
+    touches: Res<Touches>,
+    mut preview: ResMut<PathPreview>,
+    q_tiles: Query<(&Coordinates, &Interaction), With<BoardTile>>,
+    q_changed: Query<(), (Changed<Interaction>, With<BoardTile>)>,
+) {
+    // touches are previewed while dragging
+    if q_changed.is_empty() || touches.iter().next().is_some() {
+        return;
+    }
+    let target = q_tiles
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Hovered)
+        .map(|(coord, _)| *coord);
+    if preview.target != target {
+        preview.target = target;
+    }
+}
+
+// highlight the path of the selected ball to the target tile, or the target if it can't be reached.
+// Tiles of the hint are highlighted below the path
+pub fn render_path_preview(
+    board: Res<Board>,
+    preview: Res<PathPreview>,
+    mut q_tiles: Query<(&Coordinates, &mut BackgroundColor), With<BoardTile>>,
+    q_added: Query<(), Added<BoardTile>>,
+) {
+    if !board.is_changed() && !preview.is_changed() && q_added.is_empty() {
+        return;
+    }
+
+    let game_board = board.game.board();
+    let target = preview
+        .target
+        .filter(|to| board.active_ball.is_some() && game_board.get(to).is_none());
+    let path = board
+        .active_ball
+        .zip(target)
+        .and_then(|(from, to)| game_board.get_path_to_move(&from, &to));
+
+    for (coord, mut color) in q_tiles.iter_mut() {
+        let next_color = match &path {
+            Some(path) if path.contains(coord) => PATH_COLOR,
+            None if target == Some(*coord) => UNREACHABLE_COLOR,
+            _ if preview
+                .hint
+                .is_some_and(|(from, to)| from == *coord || to == *coord) =>
+            {
+                HINT_COLOR
+            }
+            _ => GameOptions::TILE_COLOR,
+        };
+        if color.0 != next_color {
+            color.0 = next_color;
+        }
+    }
+}
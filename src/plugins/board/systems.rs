@@ -1,37 +1,37 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use std::cmp::Ordering;
+use lines_core::{Game, GameConfig};
 
 use super::layout::MainCenter;
+use super::save::SavedGame;
+use super::seed::GameSeed;
 use super::GameOptions;
-use super::GameState;
-use super::IncrementCurrentGameScore;
+use super::GameScore;
+use super::HintSettings;
+use super::TurnPhase;
+use super::UndoSettings;
 
 use super::ball::*;
 use super::board::*;
 use super::events::*;
-use super::next_balls::*;
-use super::BoardTile;
 use super::Coordinates;
+use super::{BoardGrid, BoardTile};
 
-pub fn spawn_board(
-    board: ResMut<Board>,
-    mut commands: Commands,
-    main: Query<Entity, With<MainCenter>>,
-) {
-    let main = main.get_single().expect("Main not found");
+// grid of the board tiles sized by the game config
+fn spawn_board_grid(commands: &mut Commands, main: Entity, board: &mut Board) -> Entity {
+    let (width, height) = (board.game.board().width(), board.game.board().height());
+    board.tiles.clear();
 
-    commands.entity(main).with_children(|main| {
-        let mut board_bundle = main.spawn(NodeBundle {
+    let grid = commands
+        .spawn(NodeBundle {
             style: Style {
                 display: Display::Grid,
                 grid_auto_flow: GridAutoFlow::Column,
 
-                width: Val::Px(GameOptions::BOARD_SIZE),
-                height: Val::Px(GameOptions::BOARD_SIZE),
+                width: Val::Px(GameOptions::TILE_SIZE * width as f32),
+                height: Val::Px(GameOptions::TILE_SIZE * height as f32),
 
-                grid_template_columns: vec![GridTrack::flex(1.0); 9],
-                grid_template_rows: vec![GridTrack::flex(1.0); 9],
+                grid_template_columns: vec![GridTrack::flex(1.0); width as usize],
+                grid_template_rows: vec![GridTrack::flex(1.0); height as usize],
 
                 justify_content: JustifyContent::Center,
                 align_content: AlignContent::Center,
@@ -40,41 +40,63 @@ pub fn spawn_board(
             },
             background_color: GameOptions::BOARD_COLOR.into(),
             ..default()
-        });
-
-        // board tiles
-        board_bundle.with_children(|parent| {
-            let mut coordinates: Vec<&Coordinates> = board.tiles_map.keys().into_iter().collect();
-            coordinates.sort();
-
-            for coordinate in coordinates {
-                parent
-                    .spawn(NodeBundle {
-                        style: Style {
-                            width: Val::Px(GameOptions::TILE_SIZE),
-                            height: Val::Px(GameOptions::TILE_SIZE),
-                            border: UiRect::all(Val::Px(GameOptions::TILE_PADDING)),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..default()
-                        },
-                        border_color: GameOptions::BOARD_COLOR.into(),
-                        background_color: GameOptions::TILE_COLOR.into(),
-                        ..default()
-                    })
-                    .insert(*coordinate)
-                    .insert(BoardTile);
-            }
-        });
-    });
+        })
+        .insert(BoardGrid)
+        .set_parent(main)
+        .id();
+
+    // board tiles
+    for coordinate in board.game.board().coordinates() {
+        let tile = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(GameOptions::TILE_SIZE),
+                    height: Val::Px(GameOptions::TILE_SIZE),
+                    border: UiRect::all(Val::Px(GameOptions::TILE_PADDING)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: GameOptions::BOARD_COLOR.into(),
+                background_color: GameOptions::TILE_COLOR.into(),
+                ..default()
+            })
+            .insert(coordinate)
+            .insert(BoardTile)
+            .insert(Interaction::default())
+            .set_parent(grid)
+            .id();
+        board.tiles.insert(coordinate, tile);
+    }
+
+    grid
 }
 
-pub fn spawn_startup_balls(mut ev_spawn_balls: EventWriter<SpawnNewBallEvent>) {
-    // spawn startup balls
-    for _ in 0..3 {
-        let ball_color = BallColor::new();
-        ev_spawn_balls.send(SpawnNewBallEvent(ball_color));
+pub fn spawn_startup_balls(
+    mut board: ResMut<Board>,
+    mut seed: ResMut<GameSeed>,
+    mut saved_game: ResMut<SavedGame>,
+    mut config: ResMut<GameConfig>,
+    undo_settings: Res<UndoSettings>,
+    hint_settings: Res<HintSettings>,
+    mut ev_refresh: EventWriter<RefreshBoardEvent>,
+) {
+    board.active_ball = None;
+
+    // continue the game from the last session
+    if let Some(game) = saved_game.game.take() {
+        // the next games and the new game dialog keep the rules of the resumed game
+        *config = *game.config();
+        board.game = game;
+    } else {
+        board.game = Game::with_config(*config, seed.take());
+        board.game.start();
     }
+    board.game.set_undo_limit(Some(undo_settings.limit));
+    board.game.set_hint_limit(hint_settings.limit);
+
+    // the board grid may have another size than the previous game
+    ev_refresh.send(RefreshBoardEvent);
 }
 
 pub fn spawn_animation_timer(mut commands: Commands) {
@@ -82,105 +104,81 @@ pub fn spawn_animation_timer(mut commands: Commands) {
     commands.spawn(BallAnimationTimer::default());
 }
 
-pub fn render_balls(
-    mut query: Query<(&Coordinates, &mut Transform), (Changed<Coordinates>, With<Ball>)>,
+// board tiles are ui nodes, so the hit-testing follows any window size, scale or layout
+pub fn handle_mouse_clicks(
+    mouse_input: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    q_tiles: Query<(&Interaction, &Coordinates), (Changed<Interaction>, With<BoardTile>)>,
+    mut ev_input: EventWriter<BoardInputEvent>,
 ) {
-    for (coord, mut transform) in query.iter_mut() {
-        let Vec2 { x, y } = Vec2::from(*coord);
-        transform.translation.x = x;
-        transform.translation.y = y;
+    // skip the tiles pressed while the board didn't accept input, taps press the tiles as well
+    if !mouse_input.just_pressed(MouseButton::Left) && !touches.any_just_pressed() {
+        return;
+    }
+    for (interaction, coord) in q_tiles.iter() {
+        if *interaction == Interaction::Pressed {
+            ev_input.send(BoardInputEvent::Click(*coord));
+        }
     }
 }
 
-pub fn handle_mouse_clicks(
-    mouse_input: Res<Input<MouseButton>>,
+pub fn handle_board_input(
     mut board: ResMut<Board>,
     mut commands: Commands,
-    q_windows: Query<&Window, With<PrimaryWindow>>,
-    mut q_balls: Query<(&mut Coordinates, &mut Style), With<Ball>>,
-    query_next_ball: Query<&NextBall, With<NextBall>>,
-    query_tile: Query<(&Coordinates, Entity), (With<BoardTile>, Without<Ball>)>,
-    mut ev_spawn_balls: EventWriter<SpawnNewBallEvent>,
-    mut ev_change_next: EventWriter<ChangeNextBallsEvent>,
-    mut ev_inc_score: EventWriter<IncrementCurrentGameScore>,
+    mut q_balls: Query<&mut Style, With<Ball>>,
+    mut ev_input: EventReader<BoardInputEvent>,
+    mut ev_board: EventWriter<BoardEvent>,
+    mut next_phase: ResMut<NextState<TurnPhase>>,
 ) {
-    let win = q_windows.get_single().expect("no primary window");
-
-    if !mouse_input.just_pressed(MouseButton::Left) {
-        return;
-    }
-    let next_coordinates: Option<Coordinates> = win
-        .cursor_position()
-        .and_then(|position| position.try_into().ok());
+    for event in ev_input.iter() {
+        let active_ball = board
+            .active_ball
+            .and_then(|coord| board.balls.get(&coord).copied());
+
+        let BoardInputEvent::Click(next_coordinates) = *event else {
+            // unselect active ball
+            if let Some(active_ball) = active_ball {
+                commands.entity(active_ball).remove::<BallAnimationState>();
+                if let Ok(mut style) = q_balls.get_mut(active_ball) {
+                    style.top = Val::Auto;
+                }
+                board.active_ball = None;
+                next_phase.set(TurnPhase::Idle);
+            }
+            continue;
+        };
+        let ball = board.balls.get(&next_coordinates).copied();
 
-    if let Some(next_coordinates) = next_coordinates {
-        let ball = board.tiles_map.get(&next_coordinates).unwrap().as_ref();
-        match (board.active_ball, ball) {
+        match (active_ball, ball) {
             // set active ball
             (None, Some(ball)) => {
-                commands
-                    .entity(ball.entity)
-                    .insert(BallAnimationState::default());
-                board.active_ball = Some(ball.entity);
+                commands.entity(ball).insert(BallAnimationState::default());
+                board.active_ball = Some(next_coordinates);
+                next_phase.set(TurnPhase::Selecting);
             }
             // change active ball
-            (Some(active_ball), Some(ball)) if active_ball != ball.entity => {
+            (Some(active_ball), Some(ball)) if active_ball != ball => {
                 commands.entity(active_ball).remove::<BallAnimationState>();
                 // fix ball position after stop animation
-                if let Ok((_, mut style)) = q_balls.get_mut(active_ball) {
+                if let Ok(mut style) = q_balls.get_mut(active_ball) {
                     style.top = Val::Auto;
                 }
-                commands
-                    .entity(ball.entity)
-                    .insert(BallAnimationState::default());
-                board.active_ball = Some(ball.entity);
+                commands.entity(ball).insert(BallAnimationState::default());
+                board.active_ball = Some(next_coordinates);
             }
             // move active ball to new position
             (Some(active_ball), None) => {
-                if let Ok((mut coordinates, mut style)) = q_balls.get_mut(active_ball) {
-                    if coordinates.partial_cmp(&next_coordinates) != Some(Ordering::Equal)
-                        && board
-                            .get_path_to_move(&coordinates, &next_coordinates)
-                            .is_some()
-                    {
-                        // remove ball from old coordinates
-                        let ball_entity =
-                            board.tiles_map.insert(*coordinates, None).unwrap_or(None);
-                        // insert ball to new coordinates
-                        board.tiles_map.insert(next_coordinates, ball_entity);
-
-                        commands.entity(active_ball).remove::<BallAnimationState>();
+                let from = board.active_ball.unwrap();
+                if let Ok(events) = board.game.make_move(from, next_coordinates) {
+                    commands.entity(active_ball).remove::<BallAnimationState>();
+                    if let Ok(mut style) = q_balls.get_mut(active_ball) {
                         style.top = Val::Auto;
-
-                        // set new parent tile for ball
-                        for (tile_coord, tile_entity) in query_tile.iter() {
-                            if *tile_coord == next_coordinates {
-                                commands.entity(active_ball).set_parent(tile_entity);
-                            }
-                        }
-
-                        board.active_ball = None;
-
-                        // change coordinates
-                        coordinates.0 = next_coordinates.0;
-                        coordinates.1 = next_coordinates.1;
-
-                        let despawned_lines = despawn_balls_and_inc_score(
-                            &mut board,
-                            &mut commands,
-                            &mut ev_inc_score,
-                        );
-
-                        if despawned_lines == 0 {
-                            // spawn new balls
-                            query_next_ball.iter().for_each(|next_ball| {
-                                ev_spawn_balls.send(SpawnNewBallEvent(next_ball.color));
-                            });
-
-                            // change next colors
-                            ev_change_next.send(ChangeNextBallsEvent);
-                        }
                     }
+                    board.active_ball = None;
+
+                    ev_board.send_batch(events.into_iter().map(BoardEvent));
+                    // the rest of the input waits for the end of the turn
+                    break;
                 }
             }
             // do nothing
@@ -189,26 +187,29 @@ pub fn handle_mouse_clicks(
     }
 }
 
-fn despawn_balls_and_inc_score(
-    board: &mut ResMut<Board>,
+pub fn spawn_ball(
     commands: &mut Commands,
-    ev_inc_score: &mut EventWriter<IncrementCurrentGameScore>,
-) -> usize {
-    let despawned_balls = board.get_balls_for_despawn();
-    let len = despawned_balls.len();
-
-    for line in despawned_balls {
-        // set game score
-        ev_inc_score.send(IncrementCurrentGameScore(line.len() as u32 * 2));
-
-        for coordinates in line {
-            let ball = board.tiles_map.insert(coordinates, None).unwrap_or(None);
-            if let Some(ball) = ball {
-                commands.entity(ball.entity).despawn_recursive();
-            }
-        }
-    }
-    len
+    ball_assets: &BallAssets,
+    tile: Entity,
+    coord: Coordinates,
+    color: BallColor,
+) -> Entity {
+    commands
+        .spawn(ImageBundle {
+            style: Style {
+                width: Val::Px(GameOptions::BALL_SIZE),
+                height: Val::Px(GameOptions::BALL_SIZE),
+                ..default()
+            },
+            background_color: BackgroundColor(ui_color(color)),
+            image: UiImage::new(ball_assets.texture.clone()),
+            ..default()
+        })
+        .insert(Ball)
+        .insert(color)
+        .insert(coord)
+        .set_parent(tile)
+        .id()
 }
 
 pub fn animate_ball_system(
@@ -234,72 +235,212 @@ pub fn animate_ball_system(
     }
 }
 
-pub fn spawn_new_ball(
+pub fn despawn_board_balls(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    query_balls: Query<Entity, &Ball>,
+) {
+    for entity in query_balls.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    board.clear_balls();
+}
+
+// rebuild ball entities and score from the game rules state
+pub fn refresh_board(
     mut board: ResMut<Board>,
+    mut game_score: ResMut<GameScore>,
     ball_assets: Res<BallAssets>,
     mut commands: Commands,
-    q_board_tile: Query<(&Coordinates, Entity), With<BoardTile>>,
-    mut ev_spawn_balls: EventReader<SpawnNewBallEvent>,
-    mut ev_inc_score: EventWriter<IncrementCurrentGameScore>,
-    mut game_state: ResMut<NextState<GameState>>,
+    q_main: Query<Entity, With<MainCenter>>,
+    q_grid: Query<Entity, With<BoardGrid>>,
+    q_balls: Query<Entity, With<Ball>>,
+    mut next_phase: ResMut<NextState<TurnPhase>>,
+    mut ev_refresh: EventReader<RefreshBoardEvent>,
 ) {
-    let mut balls_spawned = 0;
-    for SpawnNewBallEvent(color) in ev_spawn_balls.iter() {
-        let coord = match board.get_free_tile() {
-            Some(free_coordinates) => free_coordinates,
-            None => {
-                game_state.set(GameState::GameOver);
-                return;
-            }
-        };
+    if ev_refresh.iter().count() == 0 {
+        return;
+    }
 
-        if let Some((_, tile)) = q_board_tile.iter().find(|(c, _)| **c == coord) {
-            commands.entity(tile).with_children(|parent| {
-                let entity = parent
-                    .spawn(ImageBundle {
-                        style: Style {
-                            width: Val::Px(GameOptions::BALL_SIZE),
-                            height: Val::Px(GameOptions::BALL_SIZE),
-                            ..default()
-                        },
-                        background_color: BackgroundColor(Color::from(*color)),
-                        image: UiImage::new(ball_assets.texture.clone()),
-                        ..default()
-                    })
-                    .insert(Ball)
-                    .insert(*color)
-                    .insert(coord)
-                    .id();
-
-                board
-                    .tiles_map
-                    .insert(coord, Some(BallEntity::new(*color, entity)));
-
-                balls_spawned += 1;
-            });
+    for entity in q_balls.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    board.clear_balls();
+    next_phase.set(TurnPhase::Idle);
+
+    // spawn the grid again if the board size was changed
+    let size = board.game.board().width() as usize * board.game.board().height() as usize;
+    if q_grid.is_empty() || board.tiles.len() != size {
+        for entity in q_grid.iter() {
+            commands.entity(entity).despawn_recursive();
         }
+        let main = q_main.get_single().expect("Main not found");
+        spawn_board_grid(&mut commands, main, &mut board);
+    }
 
-        if board.get_free_tile().is_none() {
-            game_state.set(GameState::GameOver);
+    let balls: Vec<_> = board.game.board().balls().collect();
+    for (coord, color) in balls {
+        if let Some(tile) = board.tiles.get(&coord).copied() {
+            let ball = spawn_ball(&mut commands, &ball_assets, tile, coord, color);
+            board.balls.insert(coord, ball);
+        }
+    }
+
+    game_score.current_score = board.game.score();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::event::ManualEventReader;
+    use lines_core::GameEvent;
+
+    // board with the tiles and the balls of the started game, without the ui
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<TurnPhase>()
+            .init_resource::<Input<MouseButton>>()
+            .init_resource::<Touches>()
+            .add_event::<BoardInputEvent>()
+            .add_event::<BoardEvent>()
+            .add_systems(Update, (handle_mouse_clicks, handle_board_input).chain());
+
+        let mut board = Board {
+            game: Game::new(42),
+            ..default()
         };
+        board.game.start();
+
+        for coord in board.game.board().coordinates().collect::<Vec<_>>() {
+            let tile = app.world.spawn((coord, BoardTile, Interaction::None)).id();
+            board.tiles.insert(coord, tile);
+        }
+        for (coord, _) in board.game.board().balls().collect::<Vec<_>>() {
+            let ball = app.world.spawn((Ball, coord, Style::default())).id();
+            board.balls.insert(coord, ball);
+        }
+        app.insert_resource(board);
+        app.update();
+        app
     }
 
-    if balls_spawned > 0 {
-        // mb new combinations after spawn new balls
-        despawn_balls_and_inc_score(&mut board, &mut commands, &mut ev_inc_score);
+    fn set_interaction(app: &mut App, coord: Coordinates, interaction: Interaction) {
+        let tile = app.world.resource::<Board>().tiles[&coord];
+        *app.world.get_mut::<Interaction>(tile).unwrap() = interaction;
     }
-}
 
-pub fn despawn_board_balls(
-    mut commands: Commands,
-    mut board: ResMut<Board>,
-    query_balls: Query<Entity, &Ball>,
-) {
-    for entity in query_balls.iter() {
-        commands.entity(entity).despawn_recursive();
+    // press the tile with the mouse for one frame
+    fn click(app: &mut App, coord: Coordinates) {
+        set_interaction(app, coord, Interaction::Pressed);
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+
+        let mut mouse_input = app.world.resource_mut::<Input<MouseButton>>();
+        mouse_input.release(MouseButton::Left);
+        mouse_input.clear();
+        set_interaction(app, coord, Interaction::Hovered);
+    }
+
+    fn clicks(app: &App, reader: &mut ManualEventReader<BoardInputEvent>) -> Vec<BoardInputEvent> {
+        let events = app.world.resource::<Events<BoardInputEvent>>();
+        reader.iter(events).copied().collect()
+    }
+
+    fn first_ball(app: &App) -> Coordinates {
+        let board = app.world.resource::<Board>();
+        board.game.board().balls().next().unwrap().0
     }
 
-    for (_coord, ball) in board.tiles_map.iter_mut() {
-        *ball = None;
+    #[test]
+    fn click_on_ball_selects_it() {
+        let mut app = app();
+        let mut reader = ManualEventReader::default();
+        let ball = first_ball(&app);
+
+        click(&mut app, ball);
+        assert_eq!(
+            clicks(&app, &mut reader),
+            vec![BoardInputEvent::Click(ball)]
+        );
+        assert_eq!(app.world.resource::<Board>().active_ball, Some(ball));
+
+        app.update();
+        assert_eq!(
+            app.world.resource::<State<TurnPhase>>().get(),
+            &TurnPhase::Selecting
+        );
+    }
+
+    #[test]
+    fn pressed_tile_without_mouse_press_is_ignored() {
+        let mut app = app();
+        let mut reader = ManualEventReader::default();
+        let ball = first_ball(&app);
+
+        set_interaction(&mut app, ball, Interaction::Pressed);
+        app.update();
+        assert!(clicks(&app, &mut reader).is_empty());
+        assert_eq!(app.world.resource::<Board>().active_ball, None);
+    }
+
+    #[test]
+    fn held_press_clicks_once() {
+        let mut app = app();
+        let mut reader = ManualEventReader::default();
+        let ball = first_ball(&app);
+
+        set_interaction(&mut app, ball, Interaction::Pressed);
+        app.world
+            .resource_mut::<Input<MouseButton>>()
+            .press(MouseButton::Left);
+        app.update();
+        // the tile stays pressed, the mouse button is held
+        app.world.resource_mut::<Input<MouseButton>>().clear();
+        app.update();
+
+        assert_eq!(clicks(&app, &mut reader).len(), 1);
+    }
+
+    #[test]
+    fn click_on_free_tile_moves_selected_ball() {
+        let mut app = app();
+        let mut ev_board = ManualEventReader::<BoardEvent>::default();
+        let ball = first_ball(&app);
+        let target = app
+            .world
+            .resource::<Board>()
+            .game
+            .board()
+            .reachable_tiles(&ball)[0];
+
+        click(&mut app, ball);
+        click(&mut app, target);
+
+        let board = app.world.resource::<Board>();
+        assert_eq!(board.active_ball, None);
+        assert_eq!(board.game.history().len(), 1);
+        assert_eq!(board.game.history()[0].from, ball);
+        assert_eq!(board.game.history()[0].to, target);
+
+        let events = app.world.resource::<Events<BoardEvent>>();
+        let moved = ev_board.iter(events).any(
+            |event| matches!(&**event, GameEvent::Moved(path) if path.last() == Some(&target)),
+        );
+        assert!(moved);
+    }
+
+    #[test]
+    fn click_on_free_tile_without_selection_does_nothing() {
+        let mut app = app();
+        let free = app.world.resource::<Board>().game.board().free_tiles()[0];
+
+        click(&mut app, free);
+        let board = app.world.resource::<Board>();
+        assert_eq!(board.active_ball, None);
+        assert!(board.game.history().is_empty());
     }
 }
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use super::board::Board;
+use super::events::BoardInputEvent;
+use super::preview::PathPreview;
+use super::BoardTile;
+use super::Coordinates;
+
+// ball dragged by the finger, the tap on it has already selected it
+#[derive(Resource, Default)]
+pub struct TouchDrag {
+    from: Option<(u64, Coordinates)>,
+}
+
+// board tile under the position in the window
+pub fn tile_at(
+    position: Vec2,
+    ui_scale: &UiScale,
+    q_tiles: &Query<(&Coordinates, &Node, &GlobalTransform), With<BoardTile>>,
+) -> Option<Coordinates> {
+    // node geometry is in the ui units, which are scaled logical pixels
+    let position = position / ui_scale.scale as f32;
+    q_tiles
+        .iter()
+        .find(|(_, node, transform)| {
+            let center = transform.translation().truncate();
+            let extents = node.size() / 2.0;
+            (position - center).abs().cmplt(extents).all()
+        })
+        .map(|(coord, _, _)| *coord)
+}
+
+// drag the ball from its tile to the target tile to move it
+pub fn touch_drag_system(
+    touches: Res<Touches>,
+    ui_scale: Res<UiScale>,
+    board: Res<Board>,
+    mut drag: ResMut<TouchDrag>,
+    mut preview: ResMut<PathPreview>,
+    q_tiles: Query<(&Coordinates, &Node, &GlobalTransform), With<BoardTile>>,
+    mut ev_input: EventWriter<BoardInputEvent>,
+) {
+    for touch in touches.iter_just_pressed() {
+        let from = tile_at(touch.position(), &ui_scale, &q_tiles);
+        if let Some(from) = from.filter(|from| board.game.board().get(from).is_some()) {
+            drag.from = Some((touch.id(), from));
+        }
+    }
+
+    let Some((id, from)) = drag.from else {
+        return;
+    };
+
+    if let Some(touch) = touches.get_pressed(id) {
+        let target = tile_at(touch.position(), &ui_scale, &q_tiles).filter(|to| *to != from);
+        if preview.target != target {
+            preview.target = target;
+        }
+        return;
+    }
+
+    // the finger was lifted or the touch was canceled
+    if let Some(touch) = touches.get_released(id) {
+        let target = tile_at(touch.position(), &ui_scale, &q_tiles).filter(|to| *to != from);
+        if let Some(target) = target {
+            ev_input.send(BoardInputEvent::Click(target));
+        }
+    }
+    drag.from = None;
+    preview.target = None;
+}
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+use lines_core::GameEvent;
+
+use super::ball::*;
+use super::board::Board;
+use super::events::*;
+use super::movement::{BallPath, MoveSettings};
+use super::systems::spawn_ball;
+use super::Coordinates;
+use super::GameOptions;
+use super::GameState;
+use super::IncrementCurrentGameScore;
+use super::TurnPhase;
+
+const CLEAR_DURATION: f32 = 0.3;
+// delay of the clear for every tile of the distance from the center of the shape
+const CLEAR_STEP: f32 = 0.04;
+const SPAWN_DURATION: f32 = 0.2;
+const POPUP_DURATION: f32 = 0.8;
+const POPUP_HEIGHT: f32 = GameOptions::TILE_SIZE;
+
+// cleared ball pops and shrinks after the delay, then it's despawned
+#[derive(Component)]
+pub struct ClearAnimation {
+    timer: Timer,
+    delay: f32,
+}
+
+impl ClearAnimation {
+    fn new(delay: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(delay + CLEAR_DURATION, TimerMode::Once),
+            delay,
+        }
+    }
+}
+
+// spawned ball grows from the center of the tile
+#[derive(Component)]
+pub struct SpawnAnimation(Timer);
+
+// floating score of the cleared line
+#[derive(Component)]
+pub struct ScorePopup(Timer);
+
+// input is accepted only when the board shows the current game rules state
+pub fn accepts_input(board: Res<Board>, phase: Res<State<TurnPhase>>) -> bool {
+    board.pending.is_empty() && matches!(phase.get(), TurnPhase::Idle | TurnPhase::Selecting)
+}
+
+pub fn queue_board_events(mut board: ResMut<Board>, mut ev_board: EventReader<BoardEvent>) {
+    for event in ev_board.iter() {
+        board.pending.push_back((**event).clone());
+    }
+}
+
+// start the animation of the next game event when the previous one is finished
+pub fn advance_turn_phase(
+    mut board: ResMut<Board>,
+    ball_assets: Res<BallAssets>,
+    move_settings: Res<MoveSettings>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+    mut q_balls: Query<&mut Coordinates, With<Ball>>,
+    q_animations: Query<(), Or<(With<BallPath>, With<ClearAnimation>, With<SpawnAnimation>)>>,
+    phase: Res<State<TurnPhase>>,
+    mut next_phase: ResMut<NextState<TurnPhase>>,
+    mut ev_inc_score: EventWriter<IncrementCurrentGameScore>,
+) {
+    if !q_animations.is_empty() {
+        return;
+    }
+
+    let Some(event) = board.pending.pop_front() else {
+        let idle = match board.active_ball {
+            Some(_) => TurnPhase::Selecting,
+            None => TurnPhase::Idle,
+        };
+        if *phase.get() != idle {
+            next_phase.set(idle);
+        }
+        return;
+    };
+
+    match event {
+        GameEvent::Moved(path) => {
+            next_phase.set(TurnPhase::Moving);
+
+            let (Some(from), Some(to)) = (path.first().copied(), path.last().copied()) else {
+                return;
+            };
+            let Some(ball) = board.balls.remove(&from) else {
+                return;
+            };
+            board.balls.insert(to, ball);
+
+            if move_settings.speed > 0.0 && path.len() > 1 {
+                commands
+                    .entity(ball)
+                    .insert(BallPath::new(path, move_settings.speed));
+                return;
+            }
+
+            // set new parent tile for ball
+            if let Some(tile) = board.tiles.get(&to) {
+                commands.entity(ball).set_parent(*tile);
+            }
+            if let Ok(mut coordinates) = q_balls.get_mut(ball) {
+                *coordinates = to;
+            }
+        }
+        GameEvent::Cleared(shape, score) => {
+            next_phase.set(TurnPhase::Clearing);
+
+            // shapes cleared by the same move are shown together
+            let mut shapes = vec![(shape, score)];
+            while let Some(GameEvent::Cleared(..)) = board.pending.front() {
+                if let Some(GameEvent::Cleared(shape, score)) = board.pending.pop_front() {
+                    shapes.push((shape, score));
+                }
+            }
+
+            let font = asset_server.load("fonts/ThinPixel7.ttf");
+            for (shape, score) in shapes {
+                // set game score
+                ev_inc_score.send(IncrementCurrentGameScore(score));
+
+                // the shape is cleared from the crossing of its lines outwards
+                let center = shape.center();
+                if let Some(tile) = board.tiles.get(&center) {
+                    spawn_score_popup(&mut commands, *tile, font.clone(), score);
+                }
+                for coord in shape.balls {
+                    if let Some(ball) = board.balls.remove(&coord) {
+                        let distance = coord.0.abs_diff(center.0).max(coord.1.abs_diff(center.1));
+                        commands
+                            .entity(ball)
+                            .insert(ClearAnimation::new(distance as f32 * CLEAR_STEP));
+                    }
+                }
+            }
+        }
+        GameEvent::Spawned(coord, color) => {
+            next_phase.set(TurnPhase::Spawning);
+
+            // balls spawned by the same turn grow together
+            let mut balls = vec![(coord, color)];
+            while let Some(GameEvent::Spawned(..)) = board.pending.front() {
+                if let Some(GameEvent::Spawned(coord, color)) = board.pending.pop_front() {
+                    balls.push((coord, color));
+                }
+            }
+
+            for (coord, color) in balls {
+                let Some(tile) = board.tiles.get(&coord).copied() else {
+                    continue;
+                };
+                let ball = spawn_ball(&mut commands, &ball_assets, tile, coord, color);
+                commands.entity(ball).insert((
+                    Style {
+                        width: Val::Px(0.0),
+                        height: Val::Px(0.0),
+                        ..default()
+                    },
+                    SpawnAnimation(Timer::from_seconds(SPAWN_DURATION, TimerMode::Once)),
+                ));
+                board.balls.insert(coord, ball);
+            }
+        }
+        GameEvent::GameOver => next_phase.set(TurnPhase::CheckingGameOver),
+    }
+}
+
+pub fn check_game_over(
+    board: Res<Board>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    // replays show the end of the game on the board
+    if board.game.is_over() && *game_state.get() == GameState::Playing {
+        next_game_state.set(GameState::GameOver);
+    }
+}
+
+fn spawn_score_popup(commands: &mut Commands, tile: Entity, font: Handle<Font>, score: u32) {
+    commands
+        .spawn(
+            TextBundle::from_section(
+                format!("+{score}"),
+                TextStyle {
+                    font,
+                    font_size: 40.0,
+                    color: Color::YELLOW_GREEN,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                ..default()
+            }),
+        )
+        .insert(ZIndex::Global(50))
+        .insert(ScorePopup(Timer::from_seconds(
+            POPUP_DURATION,
+            TimerMode::Once,
+        )))
+        .set_parent(tile);
+}
+
+pub fn animate_clear_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut q_balls: Query<(Entity, &mut Style, &mut ClearAnimation)>,
+) {
+    for (ball, mut style, mut animation) in q_balls.iter_mut() {
+        animation.timer.tick(time.delta());
+        if animation.timer.finished() {
+            commands.entity(ball).despawn_recursive();
+            continue;
+        }
+
+        // pop up a bit, then shrink to nothing
+        let elapsed = animation.timer.elapsed_secs() - animation.delay;
+        let t = (elapsed / CLEAR_DURATION).clamp(0.0, 1.0);
+        let scale = if t < 0.25 {
+            1.0 + t * 0.8
+        } else {
+            1.2 * (1.0 - (t - 0.25) / 0.75)
+        };
+        style.width = Val::Px(GameOptions::BALL_SIZE * scale);
+        style.height = Val::Px(GameOptions::BALL_SIZE * scale);
+    }
+}
+
+pub fn animate_spawn_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut q_balls: Query<(Entity, &mut Style, &mut SpawnAnimation)>,
+) {
+    for (ball, mut style, mut animation) in q_balls.iter_mut() {
+        animation.0.tick(time.delta());
+
+        let scale = animation.0.percent();
+        style.width = Val::Px(GameOptions::BALL_SIZE * scale);
+        style.height = Val::Px(GameOptions::BALL_SIZE * scale);
+
+        if animation.0.finished() {
+            commands.entity(ball).remove::<SpawnAnimation>();
+        }
+    }
+}
+
+pub fn animate_score_popup_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut q_popups: Query<(Entity, &mut Style, &mut Text, &mut ScorePopup)>,
+) {
+    for (popup, mut style, mut text, mut animation) in q_popups.iter_mut() {
+        animation.0.tick(time.delta());
+        if animation.0.finished() {
+            commands.entity(popup).despawn_recursive();
+            continue;
+        }
+
+        // float up and fade out
+        let t = animation.0.percent();
+        style.top = Val::Px(-POPUP_HEIGHT * t);
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(1.0 - t);
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use bevy::prelude::*;
 
+use lines_core::Preset;
+
+use super::board::board::Board;
+use super::board::RefreshBoardEvent;
 use super::layout::{HeaderLeft, HeaderRight, MainLeft, MainRight};
 use super::leader_board::LeaderBoard;
 use crate::{GameOptions, GameState};
@@ -21,6 +25,9 @@ pub struct BestScore;
 #[derive(Component)]
 struct LeaderName;
 
+#[derive(Component)]
+struct PresetName;
+
 #[derive(Component)]
 struct LeaderPodium;
 
@@ -35,13 +42,15 @@ impl Plugin for GameScorePlugin {
         app.init_resource::<GameScore>()
             .add_event::<IncrementCurrentGameScore>()
             .add_systems(Startup, (spawn_score_fields, spawn_score_avatars))
-            .add_systems(
-                OnEnter(GameState::Playing),
-                (init_game_score, init_leader_name),
-            )
+            .add_systems(OnEnter(GameState::Playing), init_game_score)
             .add_systems(
                 Update,
-                (game_score_system, render_score_text, podium_system),
+                (
+                    init_preset_leader,
+                    game_score_system,
+                    render_score_text,
+                    podium_system,
+                ),
             );
     }
 }
@@ -62,16 +71,39 @@ fn spawn_score_fields(
     let l_header = l_header.get_single().expect("Header left not found");
 
     commands.entity(l_header).with_children(|header| {
-        header.spawn((
-            TextBundle {
-                text: Text {
-                    sections: vec![TextSection::new("", text_style.clone())],
+        header
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
                     ..default()
                 },
                 ..default()
-            },
-            BestScore,
-        ));
+            })
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle {
+                        text: Text {
+                            sections: vec![TextSection::new("", text_style.clone())],
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    BestScore,
+                ));
+                // preset of the best score
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/ThinPixel7.ttf"),
+                            font_size: 30.0,
+                            color: Color::YELLOW_GREEN,
+                        },
+                    ),
+                    PresetName,
+                ));
+            });
     });
 
     let r_header = r_header.get_single().expect("Header right not found");
@@ -182,21 +214,34 @@ fn spawn_score_avatars(
     });
 }
 
-fn init_game_score(mut game_score: ResMut<GameScore>, leaders: Res<LeaderBoard>) {
+fn init_game_score(mut game_score: ResMut<GameScore>) {
     game_score.current_score = 0;
-    if let Some(score) = leaders.get_best_score() {
-        game_score.best_score = score;
-    }
 }
 
-fn init_leader_name(
+// best score and leader of the preset of the game shown on the board
+fn init_preset_leader(
+    board: Res<Board>,
     leaders: Res<LeaderBoard>,
+    mut game_score: ResMut<GameScore>,
     mut q_leader_name: Query<&mut Text, With<LeaderName>>,
+    mut q_preset_name: Query<&mut Text, (With<PresetName>, Without<LeaderName>)>,
+    mut ev_refresh: EventReader<RefreshBoardEvent>,
 ) {
-    if let Some(leader) = leaders.get_best_player() {
-        for mut text in &mut q_leader_name {
-            text.sections[0].value = format!("{leader}");
-        }
+    if ev_refresh.iter().count() == 0 {
+        return;
+    }
+    let preset = Preset::from_config(board.game.config());
+
+    game_score.best_score = preset
+        .and_then(|preset| leaders.get_best_score(preset))
+        .unwrap_or(0);
+    for mut text in &mut q_leader_name {
+        text.sections[0].value = preset
+            .and_then(|preset| leaders.get_best_player(preset))
+            .unwrap_or_default();
+    }
+    for mut text in &mut q_preset_name {
+        text.sections[0].value = preset.map_or("Custom", |preset| preset.name()).to_string();
     }
 }
 
@@ -219,7 +264,7 @@ fn game_score_system(
     mut game: ResMut<GameScore>,
     mut ev_inc: EventReader<IncrementCurrentGameScore>,
 ) {
-    if ev_inc.len() > 0 {
+    if !ev_inc.is_empty() {
         game.current_score += ev_inc.iter().map(|ev| ev.0).sum::<u32>();
     }
 }
@@ -240,7 +285,9 @@ fn podium_system(
             contender.height =
                 Val::Px(game.current_score as f32 / game.best_score as f32 * hight_100);
         } else {
-            leader.height = Val::Px(game.best_score as f32 / game.current_score as f32 * hight_100);
+            // custom games have no best score
+            leader.height =
+                Val::Px(game.best_score as f32 / game.current_score.max(1) as f32 * hight_100);
             contender.height = Val::Px(hight_100);
         }
     }
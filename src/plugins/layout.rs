@@ -1,12 +1,33 @@
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::GameOptions;
 
 pub struct LayoutPlugin;
 impl Plugin for LayoutPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PreStartup, setup);
+        app.init_resource::<Orientation>()
+            .add_systems(PreStartup, setup)
+            .add_systems(Update, (update_ui_scale, update_orientation).chain());
     }
 }
 
+// size of the window the ui was designed for, fixed pixel sizes are scaled to the actual window
+const LANDSCAPE_SIZE: Vec2 = Vec2::new(GameOptions::WINDOW_WIDTH, GameOptions::WINDOW_HEIGHT);
+const PORTRAIT_SIZE: Vec2 = Vec2::new(540.0, 960.0);
+
+// phones and narrow browser windows get the portrait layout: podiums are hidden,
+// the board takes the full width and the footer buttons wrap
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    #[default]
+    Landscape,
+    Portrait,
+}
+
+#[derive(Component)]
+pub struct AppFrame;
+
 #[derive(Component)]
 pub struct Header;
 
@@ -54,6 +75,7 @@ fn setup(mut commands: Commands) {
             },
             ..default()
         })
+        .insert(AppFrame)
         .with_children(|builder| {
             // Header
             builder
@@ -172,3 +194,94 @@ fn setup(mut commands: Commands) {
                 .insert(Footer);
         });
 }
+
+fn update_ui_scale(
+    q_windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut ui_scale: ResMut<UiScale>,
+    mut orientation: ResMut<Orientation>,
+) {
+    let Ok(window) = q_windows.get_single() else {
+        return;
+    };
+    let size = Vec2::new(window.width(), window.height());
+    if size.x <= 0. || size.y <= 0. {
+        return;
+    }
+
+    let (next_orientation, design_size) = if size.y > size.x {
+        (Orientation::Portrait, PORTRAIT_SIZE)
+    } else {
+        (Orientation::Landscape, LANDSCAPE_SIZE)
+    };
+    let scale = (size / design_size).min_element() as f64;
+
+    // window changes on every cursor move, so set only real changes
+    if (ui_scale.scale - scale).abs() > f64::EPSILON {
+        ui_scale.scale = scale;
+    }
+    if *orientation != next_orientation {
+        *orientation = next_orientation;
+    }
+}
+
+fn update_orientation(
+    orientation: Res<Orientation>,
+    mut q_frame: Query<&mut Style, With<AppFrame>>,
+    mut q_header: Query<&mut Style, (With<Header>, Without<AppFrame>)>,
+    mut q_main: Query<&mut Style, (With<Main>, Without<AppFrame>, Without<Header>)>,
+    mut q_sides: Query<
+        &mut Style,
+        (
+            Or<(With<MainLeft>, With<MainRight>)>,
+            Without<AppFrame>,
+            Without<Header>,
+            Without<Main>,
+        ),
+    >,
+) {
+    if !orientation.is_changed() {
+        return;
+    }
+    let portrait = *orientation == Orientation::Portrait;
+
+    for mut style in q_frame.iter_mut() {
+        style.grid_template_rows = vec![
+            GridTrack::percent(12.5),
+            GridTrack::flex(1.0),
+            GridTrack::percent(if portrait { 15.0 } else { 12.5 }),
+        ];
+    }
+    for mut style in q_header.iter_mut() {
+        style.grid_template_columns = if portrait {
+            vec![
+                GridTrack::flex(1.0),
+                GridTrack::auto(),
+                GridTrack::flex(1.0),
+            ]
+        } else {
+            vec![
+                GridTrack::percent(25.0),
+                GridTrack::flex(1.0),
+                GridTrack::percent(25.0),
+            ]
+        };
+    }
+    for mut style in q_main.iter_mut() {
+        style.grid_template_columns = if portrait {
+            vec![GridTrack::flex(1.0)]
+        } else {
+            vec![
+                GridTrack::percent(25.0),
+                GridTrack::flex(1.0),
+                GridTrack::percent(25.0),
+            ]
+        };
+    }
+    for mut style in q_sides.iter_mut() {
+        style.display = if portrait {
+            Display::None
+        } else {
+            Display::Flex
+        };
+    }
+}
@@ -1,170 +1,538 @@
 use bevy::prelude::*;
+use bevy::time::Stopwatch;
 use bevy_simple_text_input::{TextInput, TextInputSubmitEvent};
+use lines_core::{Game, GameConfig, Preset};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 
-#[cfg(target_arch = "wasm32")]
-use js_sys::JSON;
-#[cfg(target_arch = "wasm32")]
-use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use super::autoplay::Autoplay;
+use super::board::board::Board;
 use super::game_score::GameScore;
 use super::layout::MainCenter;
+use super::leader_board_file::spawn_file_buttons;
+use super::menu::{
+    spawn_button, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON, SMALL_BUTTON_WIDTH,
+};
+use super::save::SavedGame;
+use crate::input::{Action, Actions};
+use crate::storage;
 use crate::GameOptions;
 use crate::GameState;
 use crate::LeaderBoardState;
 
 const LINE_COLOR: Color = GameOptions::TILE_COLOR;
 const LINE_BORDER_COLOR: Color = GameOptions::BOARD_COLOR;
+const SORTED_COLOR: Color = Color::WHITE;
 
-const LINE_HEIGHT: f32 = GameOptions::TILE_SIZE;
+// the title and all players fit into the main area
+const LINE_HEIGHT: f32 = GameOptions::TILE_SIZE * 0.9;
 const LINE_WIDTH: f32 = GameOptions::BOARD_SIZE;
 const LINE_BORDER: f32 = GameOptions::TILE_PADDING;
 const LINE_PADDING: f32 = GameOptions::TILE_PADDING * 4.0;
 
 const MAX_PLAYERS: usize = 10;
 
+const PLAYER_NAME_KEY: &str = "player_name";
+
 pub struct LeaderBoardPlugin;
 
 impl Plugin for LeaderBoardPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(LeaderBoard::new())
+            .init_resource::<ShownPreset>()
+            .init_resource::<SortColumn>()
+            .init_resource::<PlayTime>()
+            .init_resource::<GlobalBoard>()
+            .init_resource::<UnlistedGame>()
+            .add_systems(OnEnter(GameState::Playing), reset_play_time)
             .add_systems(OnEnter(GameState::GameOver), change_leaders)
-            .add_systems(OnEnter(LeaderBoardState::InputName), spawn_leader_board)
-            .add_systems(OnEnter(LeaderBoardState::Show), spawn_leader_board)
+            .add_systems(OnEnter(LeaderBoardState::InputName), show_game_preset)
+            .add_systems(OnEnter(LeaderBoardState::Show), show_game_preset)
             .add_systems(OnEnter(LeaderBoardState::Hide), despawn_leader_board)
-            .add_systems(Update, input_field_listener);
+            .add_systems(
+                Update,
+                (
+                    play_time_system.run_if(in_state(GameState::Playing)),
+                    input_field_listener,
+                    (
+                        leader_board_keyboard_system,
+                        sort_button_system,
+                        global_button_system,
+                    )
+                        .run_if(in_state(LeaderBoardState::Show)),
+                    spawn_leader_board
+                        .run_if(
+                            resource_changed::<ShownPreset>()
+                                .or_else(resource_changed::<SortColumn>())
+                                .or_else(resource_changed::<LeaderBoard>())
+                                .or_else(resource_changed::<GlobalBoard>())
+                                .or_else(resource_changed::<UnlistedGame>()),
+                        )
+                        .run_if(not(in_state(LeaderBoardState::Hide))),
+                )
+                    .chain(),
+            );
+
+        #[cfg(feature = "online")]
+        app.add_plugins(online::OnlinePlugin);
+    }
+}
+
+// result of the ranked game, entries of the older versions have only the name, the score
+// and the hint flag, the rest is zero
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Player {
+    pub name: Option<String>,
+    pub score: u32,
+    // whether hints were used in the game
+    #[serde(default)]
+    pub hinted: bool,
+    // end of the game, seconds since the unix epoch
+    #[serde(default)]
+    pub timestamp: u64,
+    // seconds spent in the game
+    #[serde(default)]
+    pub duration: u64,
+    #[serde(default)]
+    pub moves: usize,
+    #[serde(default)]
+    pub lines: usize,
+    #[serde(default)]
+    pub longest_line: usize,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub config: GameConfig,
+}
+
+impl Player {
+    fn new(name: Option<String>, score: u32, hinted: bool, preset: Preset) -> Self {
+        Self {
+            name,
+            score,
+            hinted,
+            config: preset.config(),
+            ..default()
+        }
+    }
+
+    // the name is entered after the game
+    pub fn from_game(game: &Game, score: u32, duration: u64) -> Self {
+        let lines: Vec<usize> = game
+            .history()
+            .iter()
+            .flat_map(|turn| turn.cleared_shapes())
+            .flat_map(|shape| shape.lines.iter().map(Vec::len))
+            .collect();
+
+        Self {
+            name: None,
+            score,
+            hinted: game.hint_count() > 0,
+            timestamp: now(),
+            duration,
+            moves: game.history().len(),
+            lines: lines.len(),
+            longest_line: lines.into_iter().max().unwrap_or(0),
+            seed: game.seed(),
+            config: *game.config(),
+        }
     }
 }
 
-type Player = (Option<String>, u32);
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+const LEADER_BOARD_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct LeaderBoardFile {
+    version: u32,
+    players: Vec<Player>,
+}
 
+// separate table of players for every preset, custom games aren't ranked
 #[derive(Resource, Debug)]
 pub struct LeaderBoard {
-    pub players: Vec<Player>,
+    pub tables: HashMap<Preset, Vec<Player>>,
+    // name entered for the last game, empty when the player skipped it
+    pub last_name: Option<String>,
 }
 
 #[derive(Component)]
 struct LeaderBoardNode;
 
-impl LeaderBoard {
-    pub fn new() -> Self {
-        let mut players: Vec<Player> = match Self::get_from_local_storage() {
-            Some(players) => players,
-            None => (1..=MAX_PLAYERS)
-                .into_iter()
-                .map(|x| {
-                    (
-                        Some("Player ".to_string() + &x.to_string()),
-                        (x * 100) as u32,
-                    )
-                })
-                .collect(),
-        };
+#[derive(Component)]
+struct SortButton(Column);
 
-        players.sort_by_key(|x| !x.1); // reversed sorting
-        players.truncate(MAX_PLAYERS);
-        Self { players }
+#[derive(Component)]
+struct GlobalButton;
+
+// table shown in the overlay, none for the game with custom rules
+#[derive(Resource, Default)]
+pub struct ShownPreset(pub Option<Preset>);
+
+// best games of the leader board server, filled with the `online` feature
+#[derive(Resource, Default)]
+pub struct GlobalBoard {
+    pub tables: HashMap<Preset, Vec<Player>>,
+    // the server can't be reached, the local table is shown instead
+    pub offline: bool,
+    // the global table is shown instead of the local one
+    pub shown: bool,
+    // what happened to the last ranked game, shown under the table
+    pub notice: Option<String>,
+}
+
+// ranked game out of the local table, it gets the name for the global one with the `online` feature
+#[derive(Resource, Default)]
+pub struct UnlistedGame(pub Option<Player>);
+
+// time of the current game, paused in the other game states
+#[derive(Resource, Default)]
+pub struct PlayTime(pub Stopwatch);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Name,
+    Score,
+    Moves,
+    Lines,
+    LongestLine,
+    Duration,
+    Date,
+}
+
+impl Column {
+    const ALL: [Column; 7] = [
+        Column::Name,
+        Column::Score,
+        Column::Moves,
+        Column::Lines,
+        Column::LongestLine,
+        Column::Duration,
+        Column::Date,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Score => "Score",
+            Column::Moves => "Moves",
+            Column::Lines => "Lines",
+            Column::LongestLine => "Max",
+            Column::Duration => "Time",
+            Column::Date => "Date",
+        }
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    fn get_from_local_storage() -> Option<Vec<Player>> {
-        None
+    // percent of the line width
+    fn width(&self) -> f32 {
+        match self {
+            Column::Name => 22.0,
+            Column::Score => 15.0,
+            Column::Moves => 12.0,
+            Column::Lines => 11.0,
+            Column::LongestLine => 9.0,
+            Column::Duration => 13.0,
+            Column::Date => 18.0,
+        }
     }
 
-    #[cfg(target_arch = "wasm32")]
-    fn get_from_local_storage() -> Option<Vec<Player>> {
-        let window = web_sys::window()?;
-        let local_storage = window.local_storage().ok()??;
-        let mut players = Vec::with_capacity(10);
-
-        if let Ok(Some(leader_board)) = local_storage.get_item("leader_board") {
-            let data = JSON::parse(&leader_board).ok()?;
-            let iter = js_sys::try_iter(&data).ok()??;
-            for item in iter {
-                let item = item.ok()?;
-                let item_array: &js_sys::Array = wasm_bindgen::JsCast::dyn_ref(&item)?;
-                let name = item_array.shift().as_string();
-                let score = item_array.shift().as_f64();
-                if let (Some(name), Some(score)) = (name, score) {
-                    players.push((Some(name), score as u32));
-                }
-            }
+    // unknown values of the older entries are shown as a dash
+    fn text(&self, player: &Player) -> String {
+        let known = |value: u64| match value {
+            0 => "-".to_string(),
+            value => value.to_string(),
+        };
+        match self {
+            Column::Name => player.name.clone().unwrap_or_default(),
+            // games played with hints are flagged
+            Column::Score if player.hinted => format!("{}*", player.score),
+            Column::Score => player.score.to_string(),
+            Column::Moves => known(player.moves as u64),
+            Column::Lines => known(player.lines as u64),
+            Column::LongestLine => known(player.longest_line as u64),
+            Column::Duration if player.duration == 0 => "-".to_string(),
+            Column::Duration => format_duration(player.duration),
+            Column::Date if player.timestamp == 0 => "-".to_string(),
+            Column::Date => format_date(player.timestamp),
         }
+    }
 
-        if players.is_empty() {
-            None
-        } else {
-            Some(players)
+    fn compare(&self, a: &Player, b: &Player) -> Ordering {
+        match self {
+            Column::Name => a.name.cmp(&b.name),
+            Column::Score => a.score.cmp(&b.score),
+            Column::Moves => a.moves.cmp(&b.moves),
+            Column::Lines => a.lines.cmp(&b.lines),
+            Column::LongestLine => a.longest_line.cmp(&b.longest_line),
+            Column::Duration => a.duration.cmp(&b.duration),
+            Column::Date => a.timestamp.cmp(&b.timestamp),
         }
     }
+}
 
-    #[cfg(not(target_arch = "wasm32"))]
-    fn set_to_local_storage(_players: &Vec<Player>) -> Option<()> {
-        None
+// column of the shown table order, the table itself is always ranked by the score
+#[derive(Resource)]
+struct SortColumn {
+    column: Column,
+    descending: bool,
+}
+
+impl Default for SortColumn {
+    fn default() -> Self {
+        Self {
+            column: Column::Score,
+            descending: true,
+        }
     }
+}
 
-    #[cfg(target_arch = "wasm32")]
-    fn set_to_local_storage(players: &Vec<Player>) -> Option<()> {
-        let window = web_sys::window()?;
-        let local_storage = window.local_storage().ok()??;
-
-        let array = js_sys::Array::new();
-        for player in players {
-            let item = js_sys::Array::new();
-            item.push(&JsValue::from(player.0.clone()));
-            item.push(&JsValue::from(player.1));
-            array.push(&JsValue::from(item));
+impl SortColumn {
+    fn sorted(&self, players: &[Player]) -> Vec<Player> {
+        let mut players = players.to_vec();
+        players.sort_by(|a, b| match self.descending {
+            true => self.column.compare(b, a),
+            false => self.column.compare(a, b),
+        });
+        players
+    }
+}
+
+fn format_duration(seconds: u64) -> String {
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{hours}:{:02}:{:02}", seconds / 60 % 60, seconds % 60),
+    }
+}
+
+// day of the unix time as `dd.mm.yy`
+fn format_date(timestamp: u64) -> String {
+    // civil date from the count of days, the year starts in march
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{day:02}.{month:02}.{:02}", year % 100)
+}
+
+impl LeaderBoard {
+    pub fn new() -> Self {
+        let tables = Preset::ALL
+            .into_iter()
+            .map(|preset| {
+                let mut players: Vec<Player> = match Self::load(&Self::storage_key(preset), preset)
+                {
+                    Some(players) => players,
+                    None => (1..=MAX_PLAYERS)
+                        .map(|x| {
+                            let name = Some("Player ".to_string() + &x.to_string());
+                            Player::new(name, (x * 100) as u32, false, preset)
+                        })
+                        .collect(),
+                };
+
+                players.sort_by_key(|x| !x.score); // reversed sorting
+                players.truncate(MAX_PLAYERS);
+                (preset, players)
+            })
+            .collect();
+        Self {
+            tables,
+            last_name: storage::read(PLAYER_NAME_KEY),
+        }
+    }
+
+    // classic table keeps the key of the single table from the older versions
+    fn storage_key(preset: Preset) -> String {
+        match preset {
+            Preset::Classic => "leader_board".to_string(),
+            _ => format!("leader_board_{}", preset.name().to_lowercase()),
         }
-        if let Ok(storage_string) = JSON::stringify(&JsValue::from(array)) {
-            let storage_string: String = storage_string.into();
-            local_storage
-                .set_item("leader_board", &storage_string)
-                .unwrap();
+    }
+
+    fn load(key: &str, preset: Preset) -> Option<Vec<Player>> {
+        let data = storage::read(key)?;
+        match parse_players(&data, preset) {
+            Some(players) if !players.is_empty() => Some(players),
+            Some(_) => None,
+            None => {
+                // the corrupt table is kept aside and replaced by the default one
+                warn!("Leader board {key} is corrupt, using the default one");
+                storage::quarantine(key);
+                None
+            }
         }
-        Some(())
     }
 
-    pub fn get_best_score(&self) -> Option<u32> {
-        self.players.iter().map(|x| x.1).max()
+    fn store(key: &str, players: &[Player]) -> Option<()> {
+        let file = LeaderBoardFile {
+            version: LEADER_BOARD_VERSION,
+            players: players.to_vec(),
+        };
+        storage::write(key, &serde_json::to_string(&file).ok()?)
     }
 
-    pub fn get_lowest_score(&self) -> Option<u32> {
-        self.players.iter().map(|x| x.1).min()
+    pub fn players(&self, preset: Preset) -> &[Player] {
+        self.tables.get(&preset).map_or(&[], |players| players)
     }
 
-    pub fn add_player(&mut self, score: u32) {
-        self.players.push((None, score));
-        self.players.sort_by_key(|x| !x.1);
-        self.players.truncate(MAX_PLAYERS);
-        Self::set_to_local_storage(&self.players);
+    pub fn get_best_score(&self, preset: Preset) -> Option<u32> {
+        self.players(preset).iter().map(|x| x.score).max()
+    }
+
+    pub fn get_lowest_score(&self, preset: Preset) -> Option<u32> {
+        self.players(preset).iter().map(|x| x.score).min()
+    }
+
+    pub fn add_player(&mut self, preset: Preset, player: Player) {
+        let players = self.tables.entry(preset).or_default();
+        players.push(player);
+        players.sort_by_key(|x| !x.score);
+        players.truncate(MAX_PLAYERS);
+        Self::store(&Self::storage_key(preset), players);
+    }
+
+    // entries of another leader board, the same entries aren't added twice;
+    // returns count of the new entries kept in the tables
+    pub fn merge(&mut self, tables: BTreeMap<Preset, Vec<Player>>) -> usize {
+        let mut added = 0;
+        for (preset, imported) in tables {
+            let players = self.tables.entry(preset).or_default();
+            let before = players.clone();
+            for mut player in imported {
+                // entries without the name would wait for the input
+                player.name.get_or_insert_with(String::new);
+                if !players.contains(&player) {
+                    players.push(player);
+                }
+            }
+            players.sort_by_key(|x| !x.score);
+            players.truncate(MAX_PLAYERS);
+            added += players.iter().filter(|x| !before.contains(x)).count();
+            Self::store(&Self::storage_key(preset), players);
+        }
+        added
     }
 
     pub fn set_name(&mut self, name: String) {
-        self.players
-            .iter_mut()
-            .filter(|x| x.0.is_none())
-            .for_each(|x| {
-                x.0 = Some(name.clone());
-            });
-        Self::set_to_local_storage(&self.players);
+        for (preset, players) in self.tables.iter_mut() {
+            if players.iter().all(|x| x.name.is_some()) {
+                continue;
+            }
+            players
+                .iter_mut()
+                .filter(|x| x.name.is_none())
+                .for_each(|x| {
+                    x.name = Some(name.clone());
+                });
+            Self::store(&Self::storage_key(*preset), players);
+        }
     }
 
-    pub fn get_best_player(&self) -> Option<String> {
-        self.players
+    pub fn remember_name(&mut self, name: String) {
+        storage::write(PLAYER_NAME_KEY, &name);
+        self.last_name = Some(name);
+    }
+
+    pub fn get_best_player(&self, preset: Preset) -> Option<String> {
+        self.players(preset)
             .iter()
-            .max_by_key(|x| x.1)
-            .map(|(name, _)| name.clone())
-            .flatten()
+            .max_by_key(|x| x.score)
+            .and_then(|x| x.name.clone())
+    }
+}
+
+// versioned table, or the bare array of `[name, score, hinted]` from the older versions,
+// none for the unreadable data
+fn parse_players(data: &str, preset: Preset) -> Option<Vec<Player>> {
+    let value = serde_json::from_str::<Value>(data).ok()?;
+
+    // the first versioned tables kept players as arrays too
+    let items = match value {
+        Value::Object(ref file) => match file.get("version")?.as_u64()? {
+            1 => file.get("players")?.as_array()?.clone(),
+            2 => {
+                return serde_json::from_value::<LeaderBoardFile>(value)
+                    .ok()
+                    .map(|f| f.players)
+            }
+            _ => return None,
+        },
+        Value::Array(items) => items,
+        _ => return None,
+    };
+
+    items
+        .iter()
+        .map(|item| {
+            let name = item.get(0)?.as_str().map(str::to_string);
+            let score = item.get(1)?.as_f64()? as u32;
+            // older tables have no hint flag
+            let hinted = item.get(2).and_then(Value::as_bool).unwrap_or(false);
+            Some(Player::new(name, score, hinted, preset))
+        })
+        .collect()
+}
+
+// the resumed game goes on from the time of the last session
+fn reset_play_time(mut saved_game: ResMut<SavedGame>, mut play_time: ResMut<PlayTime>) {
+    play_time.0.reset();
+    if let Some(elapsed) = saved_game.play_time.take() {
+        play_time.0.set_elapsed(elapsed);
     }
 }
 
+fn play_time_system(time: Res<Time>, mut play_time: ResMut<PlayTime>) {
+    play_time.0.tick(time.delta());
+}
+
+// games with undone moves, custom rules or moves of the ai aren't ranked
+fn ranked_preset(game: &Game, autoplay: &Autoplay) -> Option<Preset> {
+    let preset = Preset::from_config(game.config());
+    preset.filter(|_| game.undo_count() == 0 && !autoplay.played)
+}
+
 pub fn change_leaders(
     mut leader_board: ResMut<LeaderBoard>,
     game_score: Res<GameScore>,
+    board: Res<Board>,
+    autoplay: Res<Autoplay>,
+    play_time: Res<PlayTime>,
     mut state: ResMut<NextState<LeaderBoardState>>,
 ) {
-    if let Some(score) = leader_board.get_lowest_score() {
+    let Some(preset) = ranked_preset(&board.game, &autoplay) else {
+        state.set(LeaderBoardState::Show);
+        return;
+    };
+    if let Some(score) = leader_board.get_lowest_score(preset) {
         if game_score.current_score > score {
-            leader_board.add_player(game_score.current_score);
+            let duration = play_time.0.elapsed().as_secs();
+            let player = Player::from_game(&board.game, game_score.current_score, duration);
+            leader_board.add_player(preset, player);
             state.set(LeaderBoardState::InputName);
             return;
         }
@@ -172,18 +540,113 @@ pub fn change_leaders(
     state.set(LeaderBoardState::Show);
 }
 
+fn show_game_preset(board: Res<Board>, mut shown: ResMut<ShownPreset>) {
+    shown.0 = Preset::from_config(board.game.config());
+}
+
+// left and right arrows switch the tables of the presets, up and down choose the sorted column,
+// escape or enter closes the overlay
+fn leader_board_keyboard_system(
+    actions: Actions,
+    mut shown: ResMut<ShownPreset>,
+    mut sort: ResMut<SortColumn>,
+    mut state: ResMut<NextState<LeaderBoardState>>,
+) {
+    let count = Preset::ALL.len();
+    let index = shown
+        .0
+        .and_then(|preset| Preset::ALL.iter().position(|p| *p == preset));
+
+    if actions.just_pressed(Action::Left) {
+        let index = index.map_or(count - 1, |index| (index + count - 1) % count);
+        shown.0 = Some(Preset::ALL[index]);
+    }
+    if actions.just_pressed(Action::Right) {
+        let index = index.map_or(0, |index| (index + 1) % count);
+        shown.0 = Some(Preset::ALL[index]);
+    }
+
+    let columns = Column::ALL.len();
+    let column = Column::ALL
+        .iter()
+        .position(|c| *c == sort.column)
+        .unwrap_or(0);
+    if actions.just_pressed(Action::Up) {
+        sort.column = Column::ALL[(column + columns - 1) % columns];
+    }
+    if actions.just_pressed(Action::Down) {
+        sort.column = Column::ALL[(column + 1) % columns];
+    }
+
+    if actions.just_pressed(Action::Cancel) || actions.just_pressed(Action::Confirm) {
+        state.set(LeaderBoardState::Hide);
+    }
+}
+
+// click on the column title sorts the table by it, the second click reverses the order
+fn sort_button_system(
+    q_buttons: Query<(&Interaction, &SortButton), Changed<Interaction>>,
+    mut sort: ResMut<SortColumn>,
+) {
+    for (interaction, button) in q_buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if sort.column == button.0 {
+            sort.descending = !sort.descending;
+        } else {
+            sort.column = button.0;
+            sort.descending = button.0 != Column::Name;
+        }
+    }
+}
+
+// the global table is shown in the place of the local one
+fn global_button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<GlobalButton>),
+    >,
+    mut global: ResMut<GlobalBoard>,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                global.shown = !global.shown;
+            }
+            Interaction::Hovered => *color = HOVERED_BUTTON.into(),
+            Interaction::None => *color = NORMAL_BUTTON.into(),
+        }
+    }
+}
+
 fn spawn_leader_board(
     mut commands: Commands,
     leader_board: Res<LeaderBoard>,
+    global: Res<GlobalBoard>,
+    unlisted: Res<UnlistedGame>,
+    shown: Res<ShownPreset>,
+    sort: Res<SortColumn>,
     asset_server: Res<AssetServer>,
     q_main: Query<Entity, With<MainCenter>>,
+    q_leader_board: Query<Entity, With<LeaderBoardNode>>,
 ) {
+    for entity in q_leader_board.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
     let font = asset_server.load("fonts/ThinPixel7.ttf");
     let text_style = TextStyle {
         font: font.clone(),
         font_size: 35.0,
         color: Color::DARK_GRAY,
     };
+    // the columns of the table fit into the line width
+    let cell_style = TextStyle {
+        font_size: 24.0,
+        ..text_style.clone()
+    };
 
     let main = q_main.get_single().expect("Main not found");
 
@@ -202,75 +665,183 @@ fn spawn_leader_board(
             ..default()
         })
         .with_children(|parent| {
-            for (name, value) in leader_board.players.iter() {
-                match name {
-                    Some(name) => spawn_leader_line(parent, &text_style, name, value),
-                    None => spawn_leader_input(parent, &text_style, value),
-                }
+            let Some(preset) = shown.0 else {
+                spawn_leader_title(parent, &text_style, "Custom rules are not ranked");
+                return;
+            };
+            let local = leader_board.players(preset);
+            // the new leader enters the name in the local table, the other ranked games under it
+            let editing = local.iter().any(|player| player.name.is_none()) || unlisted.0.is_some();
+            let shown_global = global.shown && !editing;
+            let global_players = global.tables.get(&preset).filter(|_| shown_global);
+
+            let title = match global_players {
+                Some(_) => format!("{} global", preset.name()),
+                None if shown_global && global.offline => format!("{} offline", preset.name()),
+                None => preset.name().to_string(),
+            };
+            spawn_leader_title(parent, &text_style, &title);
+            spawn_leader_header(parent, &cell_style, &sort);
+            let players = global_players.map_or(local, Vec::as_slice);
+            for player in sort.sorted(players).iter() {
+                spawn_leader_line(parent, &cell_style, player);
+            }
+            if let Some(player) = &unlisted.0 {
+                spawn_leader_line(parent, &cell_style, player);
+            }
+            if let Some(notice) = &global.notice {
+                spawn_leader_title(parent, &cell_style, notice);
+            }
+            if !editing {
+                spawn_table_buttons(parent, &text_style, shown_global);
             }
         })
         .insert(LeaderBoardNode);
     });
 }
 
-fn spawn_leader_line(parent: &mut ChildBuilder, text_style: &TextStyle, text: &str, value: &u32) {
+// the local table is exported, the global one is switched with the online feature
+fn spawn_table_buttons(parent: &mut ChildBuilder, text_style: &TextStyle, shown_global: bool) {
     parent
         .spawn(NodeBundle {
             style: Style {
-                width: Val::Px(LINE_WIDTH),
-                height: Val::Px(LINE_HEIGHT),
-                border: UiRect::all(Val::Px(LINE_BORDER)),
-                justify_content: JustifyContent::SpaceBetween,
-                align_items: AlignItems::Center,
-                padding: UiRect::all(Val::Px(LINE_PADDING)),
+                justify_content: JustifyContent::Center,
+                flex_wrap: FlexWrap::Wrap,
+                margin: UiRect::top(Val::Px(LINE_BORDER * 2.0)),
                 ..default()
             },
+            ..default()
+        })
+        .with_children(|parent| {
+            if !shown_global {
+                spawn_file_buttons(parent, text_style);
+            }
+            if cfg!(feature = "online") {
+                let text = if shown_global { "Local" } else { "Global" };
+                spawn_button(parent, text_style, SMALL_BUTTON_WIDTH, text, GlobalButton);
+            }
+        });
+}
+
+fn leader_line_style(justify_content: JustifyContent) -> Style {
+    Style {
+        width: Val::Px(LINE_WIDTH),
+        height: Val::Px(LINE_HEIGHT),
+        border: UiRect::all(Val::Px(LINE_BORDER)),
+        justify_content,
+        align_items: AlignItems::Center,
+        padding: UiRect::horizontal(Val::Px(LINE_PADDING)),
+        ..default()
+    }
+}
+
+fn cell_style(column: Column) -> Style {
+    Style {
+        width: Val::Percent(column.width()),
+        height: Val::Percent(100.0),
+        align_items: AlignItems::Center,
+        overflow: Overflow::clip(),
+        ..default()
+    }
+}
+
+fn spawn_leader_title(parent: &mut ChildBuilder, text_style: &TextStyle, text: &str) {
+    parent
+        .spawn(NodeBundle {
+            style: leader_line_style(JustifyContent::Center),
             border_color: BorderColor(LINE_BORDER_COLOR),
-            background_color: LINE_COLOR.into(),
+            background_color: LINE_BORDER_COLOR.into(),
             ..default()
         })
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(text, text_style.clone()));
             parent.spawn(TextBundle::from_section(
-                value.to_string(),
-                text_style.clone(),
+                text,
+                TextStyle {
+                    color: LINE_COLOR,
+                    ..text_style.clone()
+                },
             ));
         });
 }
 
-fn spawn_leader_input(parent: &mut ChildBuilder, text_style: &TextStyle, value: &u32) {
+// titles of the columns, the sorted one is highlighted and shows the order
+fn spawn_leader_header(parent: &mut ChildBuilder, text_style: &TextStyle, sort: &SortColumn) {
     parent
         .spawn(NodeBundle {
-            style: Style {
-                width: Val::Px(LINE_WIDTH),
-                height: Val::Px(LINE_HEIGHT),
-                border: UiRect::all(Val::Px(LINE_BORDER)),
-                justify_content: JustifyContent::SpaceBetween,
-                align_items: AlignItems::Center,
-                padding: UiRect::all(Val::Px(LINE_PADDING)),
-                ..default()
-            },
+            style: leader_line_style(JustifyContent::Start),
+            border_color: BorderColor(LINE_BORDER_COLOR),
+            background_color: LINE_BORDER_COLOR.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            for column in Column::ALL {
+                let (text, color) = match column == sort.column {
+                    true if sort.descending => (format!("{}v", column.title()), SORTED_COLOR),
+                    true => (format!("{}^", column.title()), SORTED_COLOR),
+                    false => (column.title().to_string(), LINE_COLOR),
+                };
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: cell_style(column),
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        SortButton(column),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            text,
+                            TextStyle {
+                                color,
+                                ..text_style.clone()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+// the name of the new leader is entered in the line
+fn spawn_leader_line(parent: &mut ChildBuilder, text_style: &TextStyle, player: &Player) {
+    parent
+        .spawn(NodeBundle {
+            style: leader_line_style(JustifyContent::Start),
             border_color: BorderColor(LINE_BORDER_COLOR),
             background_color: LINE_COLOR.into(),
             ..default()
         })
         .with_children(|parent| {
-            parent.spawn((
-                NodeBundle::default(),
-                TextInput {
-                    text_style: text_style.clone(),
+            for column in Column::ALL {
+                let mut cell = parent.spawn(NodeBundle {
+                    style: cell_style(column),
                     ..default()
-                },
-            ));
-            parent.spawn(TextBundle::from_section(
-                value.to_string(),
-                text_style.clone(),
-            ));
+                });
+                if column == Column::Name && player.name.is_none() {
+                    cell.with_children(|parent| {
+                        parent.spawn((
+                            NodeBundle::default(),
+                            TextInput {
+                                text_style: text_style.clone(),
+                                ..default()
+                            },
+                        ));
+                    });
+                } else {
+                    cell.with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            column.text(player),
+                            text_style.clone(),
+                        ));
+                    });
+                }
+            }
         });
 }
 
 fn input_field_listener(
     mut leader_board: ResMut<LeaderBoard>,
+    mut unlisted: ResMut<UnlistedGame>,
     mut events: EventReader<TextInputSubmitEvent>,
     mut state: ResMut<NextState<LeaderBoardState>>,
 ) {
@@ -280,7 +851,9 @@ fn input_field_listener(
         if cfg!(target_arch = "wasm32") {
             name.pop();
         }
-        leader_board.set_name(name);
+        leader_board.set_name(name.clone());
+        leader_board.remember_name(name);
+        unlisted.0 = None;
         state.set(LeaderBoardState::Show);
     }
 }
@@ -288,11 +861,568 @@ fn input_field_listener(
 fn despawn_leader_board(
     mut commands: Commands,
     mut leader_board: ResMut<LeaderBoard>,
+    mut unlisted: ResMut<UnlistedGame>,
     leader_board_query: Query<Entity, With<LeaderBoardNode>>,
 ) {
+    unlisted.0 = None;
     for entity in leader_board_query.iter() {
         commands.entity(entity).despawn_recursive();
         // set empty string, if user weren't enter name
         leader_board.set_name(String::from(""));
     }
 }
+
+// client of the `lines-server`: finished ranked games are sent with their replays under the last
+// entered name, the global table of the shown preset is asked when the overlay is shown
+#[cfg(feature = "online")]
+mod online {
+    use bevy::prelude::*;
+    use lines_core::{Preset, Replay};
+    use serde::Serialize;
+    use std::fmt;
+    use std::sync::mpsc::{channel, Receiver, Sender};
+    use std::sync::Mutex;
+
+    use super::{
+        change_leaders, ranked_preset, GlobalBoard, LeaderBoard, PlayTime, Player, ShownPreset,
+        UnlistedGame, MAX_PLAYERS,
+    };
+    use crate::args;
+    use crate::plugins::autoplay::Autoplay;
+    use crate::plugins::board::board::Board;
+    use crate::plugins::game_score::GameScore;
+    use crate::{GameState, LeaderBoardState};
+
+    const DEFAULT_SERVER: &str = "http://127.0.0.1:8080";
+
+    pub struct OnlinePlugin;
+
+    impl Plugin for OnlinePlugin {
+        fn build(&self, app: &mut App) {
+            app.insert_resource(OnlineClient::new())
+                .add_systems(
+                    OnEnter(GameState::GameOver),
+                    queue_submission.after(change_leaders),
+                )
+                .add_systems(OnExit(LeaderBoardState::InputName), submit_named_game)
+                .add_systems(
+                    Update,
+                    (
+                        fetch_top.run_if(resource_changed::<ShownPreset>()),
+                        receive_replies,
+                    ),
+                );
+        }
+    }
+
+    enum Reply {
+        Top(Preset, Result<Vec<Player>, RequestError>),
+        Submitted(Preset, Result<String, RequestError>),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum RequestError {
+        // the server can't be reached or its answer can't be read
+        Offline(String),
+        // the server is up, but it didn't take the request
+        Rejected(u16, String),
+    }
+
+    impl fmt::Display for RequestError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RequestError::Offline(error) => write!(f, "{error}"),
+                RequestError::Rejected(status, body) => {
+                    write!(f, "Server answered {status}: {body}")
+                }
+            }
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Submission<'a> {
+        name: &'a str,
+        replay: &'a Replay,
+    }
+
+    // replies of the requests running in the background
+    #[derive(Resource)]
+    struct OnlineClient {
+        server: String,
+        sender: Sender<Reply>,
+        receiver: Mutex<Receiver<Reply>>,
+        // finished game waiting for the name of the player
+        pending: Option<(Preset, Replay)>,
+    }
+
+    impl OnlineClient {
+        fn new() -> Self {
+            let (sender, receiver) = channel();
+            let server = args::get("server").filter(|server| !server.is_empty());
+            Self {
+                server: server.unwrap_or(DEFAULT_SERVER.to_string()),
+                sender,
+                receiver: Mutex::new(receiver),
+                pending: None,
+            }
+        }
+
+        fn send(
+            &self,
+            method: &'static str,
+            path: String,
+            body: Option<String>,
+            reply: impl FnOnce(Result<String, RequestError>) -> Reply + Send + 'static,
+        ) {
+            let url = format!("{}{path}", self.server.trim_end_matches('/'));
+            let sender = self.sender.clone();
+
+            #[cfg(not(target_arch = "wasm32"))]
+            std::thread::spawn(move || {
+                let _ = sender.send(reply(platform::request(method, &url, body)));
+            });
+
+            #[cfg(target_arch = "wasm32")]
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = sender.send(reply(platform::request(method, &url, body).await));
+            });
+        }
+
+        fn submit(&self, preset: Preset, name: &str, replay: &Replay) {
+            let submission = Submission { name, replay };
+            let Ok(body) = serde_json::to_string(&submission) else {
+                return;
+            };
+            self.send("POST", "/scores".to_string(), Some(body), move |result| {
+                Reply::Submitted(preset, result)
+            });
+        }
+
+        fn fetch_top(&self, preset: Preset) {
+            let path = format!(
+                "/scores?preset={}&limit={MAX_PLAYERS}",
+                preset.name().to_lowercase()
+            );
+            self.send("GET", path, None, move |result| {
+                let top = result.and_then(|data| {
+                    serde_json::from_str(&data)
+                        .map_err(|error| RequestError::Offline(error.to_string()))
+                });
+                Reply::Top(preset, top)
+            });
+        }
+    }
+
+    // none when the player hasn't entered the name yet or skipped it
+    fn last_name(leader_board: &LeaderBoard) -> Option<String> {
+        leader_board
+            .last_name
+            .clone()
+            .filter(|name| !name.is_empty())
+    }
+
+    // the new leader of the local table enters the name anyway, the other games are sent under
+    // the last entered name, or wait for it
+    fn queue_submission(
+        board: Res<Board>,
+        autoplay: Res<Autoplay>,
+        game_score: Res<GameScore>,
+        play_time: Res<PlayTime>,
+        leader_board: Res<LeaderBoard>,
+        mut unlisted: ResMut<UnlistedGame>,
+        mut global: ResMut<GlobalBoard>,
+        mut client: ResMut<OnlineClient>,
+        mut state: ResMut<NextState<LeaderBoardState>>,
+    ) {
+        let Some(preset) = ranked_preset(&board.game, &autoplay) else {
+            return;
+        };
+        let replay = Replay::from_game(&board.game);
+        global.notice = None;
+
+        if leader_board
+            .players(preset)
+            .iter()
+            .any(|player| player.name.is_none())
+        {
+            client.pending = Some((preset, replay));
+            return;
+        }
+        match last_name(&leader_board) {
+            Some(name) => {
+                client.submit(preset, &name, &replay);
+                global.notice = Some(format!("Sending the game of {name}"));
+            }
+            None => {
+                let duration = play_time.0.elapsed().as_secs();
+                let player = Player::from_game(&board.game, game_score.current_score, duration);
+                unlisted.0 = Some(player);
+                client.pending = Some((preset, replay));
+                state.set(LeaderBoardState::InputName);
+            }
+        }
+    }
+
+    // the input is closed before the overlay, the game without the entered name isn't sent
+    fn submit_named_game(
+        leader_board: Res<LeaderBoard>,
+        unlisted: Res<UnlistedGame>,
+        mut global: ResMut<GlobalBoard>,
+        mut client: ResMut<OnlineClient>,
+    ) {
+        let Some((preset, replay)) = client.pending.take() else {
+            return;
+        };
+        let unnamed = unlisted.0.is_some()
+            || leader_board
+                .players(preset)
+                .iter()
+                .any(|player| player.name.is_none());
+
+        match last_name(&leader_board).filter(|_| !unnamed) {
+            Some(name) => {
+                client.submit(preset, &name, &replay);
+                global.notice = Some(format!("Sending the game of {name}"));
+            }
+            None => global.notice = Some("Game without the name isn't sent".to_string()),
+        }
+    }
+
+    fn fetch_top(shown: Res<ShownPreset>, client: Res<OnlineClient>) {
+        if let Some(preset) = shown.0 {
+            client.fetch_top(preset);
+        }
+    }
+
+    // the local table is shown while the server can't be reached
+    fn receive_replies(client: Res<OnlineClient>, mut global: ResMut<GlobalBoard>) {
+        let replies: Vec<Reply> = client
+            .receiver
+            .lock()
+            .expect("Receiver lock")
+            .try_iter()
+            .collect();
+
+        for reply in replies {
+            match reply {
+                Reply::Top(preset, Ok(players)) => {
+                    global.tables.insert(preset, players);
+                    global.offline = false;
+                }
+                Reply::Submitted(preset, Ok(reply)) => {
+                    info!("Game is sent to the leader board server: {reply}");
+                    let rank = serde_json::from_str::<serde_json::Value>(&reply)
+                        .ok()
+                        .and_then(|reply| reply.get("rank")?.as_u64());
+                    global.notice = Some(match rank {
+                        Some(rank) => format!("Game is sent, global rank {rank}"),
+                        None => "Game is sent, too low for the global table".to_string(),
+                    });
+                    client.fetch_top(preset);
+                }
+                Reply::Top(_, Err(error @ RequestError::Offline(_))) => {
+                    warn!("Leader board server {}: {error}", client.server);
+                    global.offline = true;
+                }
+                Reply::Submitted(_, Err(error @ RequestError::Offline(_))) => {
+                    warn!("Leader board server {}: {error}", client.server);
+                    global.notice = Some("Game isn't sent, the server is offline".to_string());
+                    global.offline = true;
+                }
+                // e.g. the game of the custom rules, the server is still up
+                Reply::Top(_, Err(error)) => {
+                    warn!(
+                        "Leader board server {} rejected the request: {error}",
+                        client.server
+                    );
+                }
+                Reply::Submitted(_, Err(error)) => {
+                    warn!(
+                        "Leader board server {} rejected the game: {error}",
+                        client.server
+                    );
+                    global.notice = Some("Game is rejected by the server".to_string());
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    mod platform {
+        use std::io::{Read, Write};
+        use std::net::{TcpStream, ToSocketAddrs};
+        use std::time::Duration;
+
+        use super::RequestError;
+
+        const TIMEOUT: Duration = Duration::from_secs(5);
+
+        // plain http/1.1 of the lan server, the body of the successful reply
+        pub fn request(
+            method: &str,
+            url: &str,
+            body: Option<String>,
+        ) -> Result<String, RequestError> {
+            let offline = RequestError::Offline;
+            let rest = url
+                .strip_prefix("http://")
+                .ok_or(offline(format!("Only http servers are supported: {url}")))?;
+            let (host, path) = match rest.find('/') {
+                Some(index) => rest.split_at(index),
+                None => (rest, "/"),
+            };
+            let addr = match host.contains(':') {
+                true => host.to_string(),
+                false => format!("{host}:80"),
+            };
+            let addr = addr
+                .to_socket_addrs()
+                .map_err(|error| offline(error.to_string()))?
+                .next()
+                .ok_or(offline(format!("Unknown host {host}")))?;
+
+            let error = |error: std::io::Error| offline(error.to_string());
+            let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT).map_err(error)?;
+            stream.set_read_timeout(Some(TIMEOUT)).map_err(error)?;
+            stream.set_write_timeout(Some(TIMEOUT)).map_err(error)?;
+
+            let body = body.unwrap_or_default();
+            write!(
+                stream,
+                "{method} {path} HTTP/1.1\r\n\
+                 Host: {host}\r\n\
+                 Content-Type: application/json\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .map_err(error)?;
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).map_err(error)?;
+            let malformed = || offline("Malformed response".to_string());
+            let (head, body) = response.split_once("\r\n\r\n").ok_or_else(malformed)?;
+            let status: u16 = head
+                .split_whitespace()
+                .nth(1)
+                .and_then(|status| status.parse().ok())
+                .ok_or_else(malformed)?;
+
+            match status {
+                200..=299 => Ok(body.to_string()),
+                _ => Err(RequestError::Rejected(status, body.to_string())),
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use std::io::{BufRead, BufReader};
+            use std::net::TcpListener;
+
+            // answers one request with the response, returns the request line; the whole request
+            // is read, the unread body would reset the connection before the response
+            fn serve_once(response: &'static str) -> (String, std::thread::JoinHandle<String>) {
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let url = format!("http://{}", listener.local_addr().unwrap());
+                let handle = std::thread::spawn(move || {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(&stream);
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let mut length = 0;
+                    loop {
+                        let mut header = String::new();
+                        reader.read_line(&mut header).unwrap();
+                        if header.trim().is_empty() {
+                            break;
+                        }
+                        if let Some(value) = header.strip_prefix("Content-Length:") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                    reader.read_exact(&mut vec![0; length]).unwrap();
+                    stream.write_all(response.as_bytes()).unwrap();
+                    line
+                });
+                (url, handle)
+            }
+
+            #[test]
+            fn read_successful_reply() {
+                let (url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n[]");
+                let reply = request("GET", &format!("{url}/scores?preset=mini"), None);
+
+                assert_eq!(reply, Ok("[]".to_string()));
+                assert_eq!(
+                    server.join().unwrap(),
+                    "GET /scores?preset=mini HTTP/1.1\r\n"
+                );
+            }
+
+            #[test]
+            fn report_failed_requests() {
+                let (url, _) = serve_once("HTTP/1.1 422 Unprocessable Entity\r\n\r\n{}");
+                let reply = request("POST", &format!("{url}/scores"), Some("{}".to_string()));
+                assert_eq!(reply, Err(RequestError::Rejected(422, "{}".to_string())));
+
+                assert!(matches!(
+                    request("GET", "https://localhost/scores", None),
+                    Err(RequestError::Offline(_))
+                ));
+                // nobody listens on the port of the finished server
+                let (url, server) = serve_once("HTTP/1.1 200 OK\r\n\r\n");
+                request("GET", &url, None).unwrap();
+                server.join().unwrap();
+                assert!(matches!(
+                    request("GET", &url, None),
+                    Err(RequestError::Offline(_))
+                ));
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    mod platform {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_futures::JsFuture;
+
+        use super::RequestError;
+
+        // text body is sent without the preflight request
+        pub async fn request(
+            method: &str,
+            url: &str,
+            body: Option<String>,
+        ) -> Result<String, RequestError> {
+            let error = |error: JsValue| RequestError::Offline(format!("{error:?}"));
+            let mut init = web_sys::RequestInit::new();
+            init.method(method);
+            if let Some(body) = &body {
+                init.body(Some(&JsValue::from_str(body)));
+            }
+            let request = web_sys::Request::new_with_str_and_init(url, &init).map_err(error)?;
+
+            let window = web_sys::window().ok_or(RequestError::Offline("No window".to_string()))?;
+            let response: web_sys::Response = JsFuture::from(window.fetch_with_request(&request))
+                .await
+                .map_err(error)?
+                .dyn_into()
+                .map_err(error)?;
+            let text = JsFuture::from(response.text().map_err(error)?)
+                .await
+                .map_err(error)?
+                .as_string()
+                .unwrap_or_default();
+
+            match response.ok() {
+                true => Ok(text),
+                false => Err(RequestError::Rejected(response.status(), text)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name: Option<&str>, score: u32, hinted: bool) -> Player {
+        Player::new(name.map(str::to_string), score, hinted, Preset::Classic)
+    }
+
+    #[test]
+    fn parse_versioned_table() {
+        let entry = Player {
+            timestamp: 1_700_000_000,
+            duration: 754,
+            moves: 120,
+            lines: 14,
+            longest_line: 6,
+            seed: 42,
+            ..player(Some("Ann"), 120, true)
+        };
+        let data = serde_json::to_string(&LeaderBoardFile {
+            version: LEADER_BOARD_VERSION,
+            players: vec![entry.clone(), player(None, 80, false)],
+        })
+        .unwrap();
+
+        let players = parse_players(&data, Preset::Classic).unwrap();
+        assert_eq!(players, vec![entry, player(None, 80, false)]);
+    }
+
+    #[test]
+    fn migrate_older_tables() {
+        let data = r#"{"version":1,"players":[["Ann",120,true],[null,80,false]]}"#;
+        let players = parse_players(data, Preset::Classic).unwrap();
+        assert_eq!(
+            players,
+            vec![player(Some("Ann"), 120, true), player(None, 80, false)]
+        );
+
+        // bare arrays of the local storage in the browser
+        let players = parse_players(r#"[["Ann",120],["Bob",80,true]]"#, Preset::Easy).unwrap();
+        assert_eq!(players[0].name.as_deref(), Some("Ann"));
+        assert_eq!(players[0].score, 120);
+        assert!(players[1].hinted);
+        assert_eq!(players[1].config, Preset::Easy.config());
+    }
+
+    #[test]
+    fn reject_corrupt_table() {
+        let parse = |data| parse_players(data, Preset::Classic);
+        assert_eq!(parse(r#"[["Ann",120],"#), None);
+        assert_eq!(parse(r#"{"players":3}"#), None);
+        assert_eq!(parse(r#"[["Ann"]]"#), None);
+        // tables of the newer versions aren't read
+        assert_eq!(parse(r#"{"version":99,"players":[]}"#), None);
+    }
+
+    #[test]
+    fn sort_shown_table() {
+        let players = vec![
+            Player {
+                moves: 30,
+                ..player(Some("Bob"), 300, false)
+            },
+            Player {
+                moves: 50,
+                ..player(Some("Ann"), 200, false)
+            },
+        ];
+        let names = |sort: SortColumn| {
+            sort.sorted(&players)
+                .into_iter()
+                .filter_map(|player| player.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(SortColumn::default()), ["Bob", "Ann"]);
+        let by_moves = SortColumn {
+            column: Column::Moves,
+            descending: true,
+        };
+        assert_eq!(names(by_moves), ["Ann", "Bob"]);
+        let by_name = SortColumn {
+            column: Column::Name,
+            descending: false,
+        };
+        assert_eq!(names(by_name), ["Ann", "Bob"]);
+    }
+
+    #[test]
+    fn format_columns() {
+        assert_eq!(format_date(0), "01.01.70");
+        assert_eq!(format_date(1_709_251_200), "01.03.24");
+        assert_eq!(format_date(1_792_281_600), "18.10.26");
+        assert_eq!(format_duration(754), "12:34");
+        assert_eq!(format_duration(3_725), "1:02:05");
+
+        let old = player(Some("Ann"), 120, true);
+        assert_eq!(Column::Score.text(&old), "120*");
+        assert_eq!(Column::Moves.text(&old), "-");
+        assert_eq!(Column::Date.text(&old), "-");
+    }
+}
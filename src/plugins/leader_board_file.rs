@@ -0,0 +1,505 @@
+use bevy::prelude::*;
+use lines_core::{GameConfig, Preset, ScoringRule};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::leader_board::{LeaderBoard, Player, ShownPreset};
+use super::menu::{
+    spawn_button, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON, SMALL_BUTTON_WIDTH,
+};
+use crate::LeaderBoardState;
+
+const FILE_VERSION: u32 = 1;
+
+const CSV_HEADER: &str = "preset,name,score,hinted,timestamp,duration,moves,lines,longest_line,\
+    seed,width,height,colors,line_length,balls_per_turn,starting_balls,scoring";
+
+// leader boards exported to files and merged back from the files of other players:
+// `--export-scores path.json` (or `.csv`) and `--import-scores path` on desktop, the buttons
+// of the leader board overlay, and a file dropped onto the window
+pub struct LeaderBoardFilePlugin;
+
+impl Plugin for LeaderBoardFilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            file_button_system.run_if(in_state(LeaderBoardState::Show)),
+        );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Startup, platform::files_from_args)
+            .add_systems(Update, platform::dropped_file_system);
+
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(Update, platform::picked_file_system);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Csv => "csv",
+        }
+    }
+
+    fn from_path(path: &str) -> Self {
+        match path.to_lowercase().ends_with(".csv") {
+            true => Format::Csv,
+            false => Format::Json,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum FileButton {
+    Export(Format),
+    #[cfg(target_arch = "wasm32")]
+    Import,
+}
+
+// players of every preset
+type Tables = BTreeMap<Preset, Vec<Player>>;
+
+#[derive(Serialize, Deserialize)]
+struct ScoresFile {
+    version: u32,
+    tables: Tables,
+}
+
+// export buttons of the shown table, the file of another leader board is opened
+// in the browser, desktop takes the dropped files
+pub fn spawn_file_buttons(parent: &mut ChildBuilder, text_style: &TextStyle) {
+    let buttons = [
+        ("JSON", FileButton::Export(Format::Json)),
+        ("CSV", FileButton::Export(Format::Csv)),
+        #[cfg(target_arch = "wasm32")]
+        ("Import", FileButton::Import),
+    ];
+    for (text, button) in buttons {
+        spawn_button(parent, text_style, SMALL_BUTTON_WIDTH, text, button);
+    }
+}
+
+fn file_button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &FileButton),
+        Changed<Interaction>,
+    >,
+    leader_board: Res<LeaderBoard>,
+    shown: Res<ShownPreset>,
+) {
+    for (interaction, mut color, button) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                match button {
+                    FileButton::Export(format) => {
+                        let Some(preset) = shown.0 else {
+                            continue;
+                        };
+                        let name = format!(
+                            "lines_{}_scores.{}",
+                            preset.name().to_lowercase(),
+                            format.extension()
+                        );
+                        let tables = export_tables(&leader_board, Some(preset));
+                        platform::save_file(&name, &to_format(&tables, *format));
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    FileButton::Import => {
+                        platform::pick_file();
+                    }
+                }
+            }
+            Interaction::Hovered => *color = HOVERED_BUTTON.into(),
+            Interaction::None => *color = NORMAL_BUTTON.into(),
+        }
+    }
+}
+
+// all tables, or the table of the preset
+fn export_tables(leader_board: &LeaderBoard, preset: Option<Preset>) -> Tables {
+    Preset::ALL
+        .into_iter()
+        .filter(|p| preset.is_none_or(|preset| preset == *p))
+        .map(|p| (p, leader_board.players(p).to_vec()))
+        .collect()
+}
+
+fn import(leader_board: &mut LeaderBoard, data: &str) {
+    match parse(data) {
+        Some(tables) => {
+            let added = leader_board.merge(tables);
+            info!("Imported {added} new leader board entries");
+        }
+        None => warn!("File is not a leader board"),
+    }
+}
+
+fn to_format(tables: &Tables, format: Format) -> String {
+    match format {
+        Format::Json => to_json(tables),
+        Format::Csv => to_csv(tables),
+    }
+}
+
+pub fn to_json(tables: &Tables) -> String {
+    let file = ScoresFile {
+        version: FILE_VERSION,
+        tables: tables.clone(),
+    };
+    serde_json::to_string_pretty(&file).unwrap_or_default()
+}
+
+// one row per entry, the rules of the game are split into columns
+pub fn to_csv(tables: &Tables) -> String {
+    let mut csv = format!("{CSV_HEADER}\n");
+    for (preset, players) in tables {
+        for player in players {
+            let config = player.config;
+            let fields = [
+                preset.name().to_string(),
+                csv_field(player.name.as_deref().unwrap_or_default()),
+                player.score.to_string(),
+                player.hinted.to_string(),
+                player.timestamp.to_string(),
+                player.duration.to_string(),
+                player.moves.to_string(),
+                player.lines.to_string(),
+                player.longest_line.to_string(),
+                player.seed.to_string(),
+                config.width.to_string(),
+                config.height.to_string(),
+                config.colors.to_string(),
+                config.line_length.to_string(),
+                config.balls_per_turn.to_string(),
+                config.starting_balls.to_string(),
+                config.scoring.name().to_string(),
+            ];
+            csv += &fields.join(",");
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+// tables of the json or csv file, none if any entry can't be read. Entries played by
+// the rules of another preset are left out
+pub fn parse(data: &str) -> Option<Tables> {
+    let mut tables = match data.trim_start().starts_with('{') {
+        true => {
+            let file = serde_json::from_str::<ScoresFile>(data).ok()?;
+            (file.version <= FILE_VERSION).then_some(file.tables)?
+        }
+        false => parse_csv(data)?,
+    };
+
+    let mut skipped = 0;
+    for (preset, players) in &mut tables {
+        let count = players.len();
+        players.retain(|player| player.config == preset.config());
+        skipped += count - players.len();
+    }
+    if skipped > 0 {
+        warn!("Skipped {skipped} entries with the rules of another preset");
+    }
+    Some(tables)
+}
+
+// columns are found by the header, so the files edited in spreadsheets can be read too;
+// only the preset, the name and the score are required
+fn parse_csv(data: &str) -> Option<Tables> {
+    let mut rows = split_csv(data)
+        .into_iter()
+        .filter(|row| row.iter().any(|field| !field.trim().is_empty()));
+    let header = rows.next()?;
+    let column = |name: &str| header.iter().position(|title| title.trim() == name);
+
+    let mut tables = Tables::new();
+    for fields in rows {
+        let field = |name: &str| {
+            column(name)
+                .and_then(|index| fields.get(index))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+
+        let preset = field("preset")?;
+        let preset = Preset::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(preset))?;
+        let default = preset.config();
+        let scoring = match field("scoring") {
+            Some(name) => ScoringRule::ALL
+                .into_iter()
+                .find(|rule| rule.name().eq_ignore_ascii_case(name))?,
+            None => default.scoring,
+        };
+
+        let player = Player {
+            name: Some(column("name").and_then(|index| fields.get(index))?.clone()),
+            score: field("score")?.parse().ok()?,
+            hinted: number(field("hinted"), false)?,
+            timestamp: number(field("timestamp"), 0)?,
+            duration: number(field("duration"), 0)?,
+            moves: number(field("moves"), 0)?,
+            lines: number(field("lines"), 0)?,
+            longest_line: number(field("longest_line"), 0)?,
+            seed: number(field("seed"), 0)?,
+            config: GameConfig {
+                width: number(field("width"), default.width)?,
+                height: number(field("height"), default.height)?,
+                colors: number(field("colors"), default.colors)?,
+                line_length: number(field("line_length"), default.line_length)?,
+                balls_per_turn: number(field("balls_per_turn"), default.balls_per_turn)?,
+                starting_balls: number(field("starting_balls"), default.starting_balls)?,
+                scoring,
+            },
+        };
+        tables.entry(preset).or_default().push(player);
+    }
+    Some(tables)
+}
+
+// the missing value is the default, the wrong one is an error
+fn number<T: FromStr>(field: Option<&str>, default: T) -> Option<T> {
+    match field {
+        Some(field) => field.parse().ok(),
+        None => Some(default),
+    }
+}
+
+// names with commas, quotes or line breaks are quoted
+fn csv_field(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
+
+// rows of the fields, the quoted fields may have line breaks
+fn split_csv(data: &str) -> Vec<Vec<String>> {
+    let mut rows = vec![vec![String::new()]];
+    let mut quoted = false;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let row = rows.last_mut().expect("Data has a row");
+        let field = row.last_mut().expect("Row has a field");
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(String::new()),
+            '\n' if !quoted => rows.push(vec![String::new()]),
+            '\r' if !quoted => (),
+            _ => field.push(c),
+        }
+    }
+    rows
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use bevy::prelude::*;
+    use bevy::window::FileDragAndDrop;
+    use std::fs;
+
+    use super::{export_tables, import, to_format, Format};
+    use crate::args;
+    use crate::plugins::leader_board::LeaderBoard;
+
+    // exported files are written to the downloads folder
+    pub fn save_file(name: &str, data: &str) -> Option<()> {
+        let path = dirs::download_dir().or_else(dirs::home_dir)?.join(name);
+        match fs::write(&path, data) {
+            Ok(_) => info!("Leader board is exported to {}", path.display()),
+            Err(error) => warn!("Can't write {}: {error}", path.display()),
+        }
+        Some(())
+    }
+
+    pub fn files_from_args(mut leader_board: ResMut<LeaderBoard>) {
+        if let Some(path) = args::get("import-scores").filter(|path| !path.is_empty()) {
+            match fs::read_to_string(&path) {
+                Ok(data) => import(&mut leader_board, &data),
+                Err(error) => warn!("Can't read {path}: {error}"),
+            }
+        }
+        if let Some(path) = args::get("export-scores").filter(|path| !path.is_empty()) {
+            let data = to_format(
+                &export_tables(&leader_board, None),
+                Format::from_path(&path),
+            );
+            match fs::write(&path, data) {
+                Ok(_) => info!("Leader board is exported to {path}"),
+                Err(error) => warn!("Can't write {path}: {error}"),
+            }
+        }
+    }
+
+    pub fn dropped_file_system(
+        mut events: EventReader<FileDragAndDrop>,
+        mut leader_board: ResMut<LeaderBoard>,
+    ) {
+        for event in events.iter() {
+            if let FileDragAndDrop::DroppedFile { path_buf, .. } = event {
+                match fs::read_to_string(path_buf) {
+                    Ok(data) => import(&mut leader_board, &data),
+                    Err(error) => warn!("Can't read {}: {error}", path_buf.display()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use bevy::prelude::*;
+    use std::cell::RefCell;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    use super::import;
+    use crate::plugins::leader_board::LeaderBoard;
+
+    thread_local! {
+        // text of the file chosen in the browser dialog, taken by the next frame
+        static PICKED_FILE: RefCell<Option<String>> = RefCell::new(None);
+    }
+
+    // the browser downloads the link to the data
+    pub fn save_file(name: &str, data: &str) -> Option<()> {
+        let document = web_sys::window()?.document()?;
+        let parts = js_sys::Array::of1(&JsValue::from_str(data));
+        let blob = web_sys::Blob::new_with_str_sequence(&parts).ok()?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob).ok()?;
+
+        let link: web_sys::HtmlAnchorElement =
+            document.create_element("a").ok()?.dyn_into().ok()?;
+        link.set_href(&url);
+        link.set_download(name);
+        link.click();
+        web_sys::Url::revoke_object_url(&url).ok()
+    }
+
+    pub fn pick_file() -> Option<()> {
+        let document = web_sys::window()?.document()?;
+        let input: web_sys::HtmlInputElement =
+            document.create_element("input").ok()?.dyn_into().ok()?;
+        input.set_type("file");
+        input.set_accept(".json,.csv");
+
+        let target = input.clone();
+        let on_change = Closure::once(move || {
+            let Some(file) = target.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let on_load = Closure::once(move |text: JsValue| {
+                PICKED_FILE.with(|picked| *picked.borrow_mut() = text.as_string());
+            });
+            let _ = file.text().then(&on_load);
+            on_load.forget();
+        });
+        input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+        on_change.forget();
+        input.click();
+        Some(())
+    }
+
+    pub fn picked_file_system(mut leader_board: ResMut<LeaderBoard>) {
+        if let Some(data) = PICKED_FILE.with(|picked| picked.borrow_mut().take()) {
+            import(&mut leader_board, &data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables() -> Tables {
+        let ann = Player {
+            name: Some("Ann, \"the first\"\nof the line".to_string()),
+            score: 250,
+            hinted: true,
+            timestamp: 1_792_281_600,
+            duration: 754,
+            moves: 120,
+            lines: 14,
+            longest_line: 7,
+            seed: 42,
+            config: Preset::Classic.config(),
+        };
+        let bob = Player {
+            name: Some("Bob".to_string()),
+            score: 90,
+            config: Preset::Mini.config(),
+            ..default()
+        };
+        Tables::from([(Preset::Classic, vec![ann]), (Preset::Mini, vec![bob])])
+    }
+
+    #[test]
+    fn json_round_trip() {
+        assert_eq!(parse(&to_json(&tables())), Some(tables()));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let csv = to_csv(&tables());
+        assert!(csv.starts_with(CSV_HEADER));
+        // the name of ann takes two lines
+        assert_eq!(csv.lines().count(), 4);
+        assert_eq!(parse(&csv), Some(tables()));
+
+        // line ends of windows
+        let crlf = parse("preset,name,score\r\nClassic,Ann,100\r\n").unwrap();
+        assert_eq!(crlf[&Preset::Classic][0].name.as_deref(), Some("Ann"));
+    }
+
+    #[test]
+    fn parse_edited_csv() {
+        // reordered columns, without the rules and details of the game
+        let csv = "score,name,preset\n300,Carol,easy\n\n120,,Hard\n";
+        let tables = parse(csv).unwrap();
+
+        let carol = &tables[&Preset::Easy][0];
+        assert_eq!(carol.name.as_deref(), Some("Carol"));
+        assert_eq!(carol.score, 300);
+        assert_eq!(carol.config, Preset::Easy.config());
+        assert_eq!(tables[&Preset::Hard][0].name.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn skip_entries_of_other_rules() {
+        let csv = "preset,name,score,colors\nClassic,Ann,300,5\nClassic,Bob,200,7\n";
+        let classic = &parse(csv).unwrap()[&Preset::Classic];
+        assert_eq!(classic.len(), 1);
+        assert_eq!(classic[0].name.as_deref(), Some("Bob"));
+
+        // the mini game in the classic table
+        let mut mixed = tables();
+        let bob = mixed[&Preset::Mini][0].clone();
+        mixed.get_mut(&Preset::Classic).unwrap().push(bob);
+        assert_eq!(parse(&to_json(&mixed)), Some(tables()));
+    }
+
+    #[test]
+    fn reject_broken_files() {
+        assert_eq!(parse("preset,name,score\nClassic,Ann,many\n"), None);
+        assert_eq!(parse("preset,name,score\nCustom,Ann,100\n"), None);
+        assert_eq!(parse("name,score\nAnn,100\n"), None);
+        assert_eq!(parse(r#"{"version":1,"tables":"#), None);
+    }
+}
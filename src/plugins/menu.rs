@@ -1,39 +1,80 @@
 use bevy::ecs::component::Component;
 use bevy::prelude::*;
 
+use super::board::{HintEvent, HintSettings, HistoryEvent, UndoSettings};
 use super::layout::Footer;
+use super::replay::LastReplay;
+use crate::input::{Action, Actions};
 use crate::GameOptions;
 use crate::GameState;
+use crate::InputFocus;
 use crate::LeaderBoardState;
+use crate::NewGameState;
+use crate::ResumeState;
 
-const NORMAL_BUTTON: Color = GameOptions::TILE_COLOR;
-const HOVERED_BUTTON: Color = Color::rgb(0.80, 0.80, 0.80);
-const PRESSED_BUTTON: Color = Color::rgb(0.90, 0.90, 0.90);
+pub const NORMAL_BUTTON: Color = GameOptions::TILE_COLOR;
+pub const HOVERED_BUTTON: Color = Color::rgb(0.80, 0.80, 0.80);
+pub const PRESSED_BUTTON: Color = Color::rgb(0.90, 0.90, 0.90);
 const BUTTON_BORDER_COLOR: Color = GameOptions::BOARD_COLOR;
+const FOCUSED_BORDER_COLOR: Color = Color::YELLOW_GREEN;
 
 const BUTTON_HEIGHT: f32 = GameOptions::TILE_SIZE;
-const BUTTON_WIDTH: f32 = BUTTON_HEIGHT * 5.0;
+pub const BUTTON_WIDTH: f32 = BUTTON_HEIGHT * 3.75;
+pub const SMALL_BUTTON_WIDTH: f32 = BUTTON_HEIGHT * 2.5;
 const BUTTON_BORDER: f32 = GameOptions::TILE_PADDING;
 
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup)
+        app.init_resource::<MenuFocus>()
+            .add_event::<MenuEvent>()
+            .add_systems(Startup, setup)
             .add_systems(OnEnter(GameState::Restarting), start_game)
-            .add_systems(Update, button_system);
+            .add_systems(
+                Update,
+                (
+                    button_system,
+                    menu_keyboard_system
+                        .run_if(not(in_state(GameState::Replaying)))
+                        .run_if(in_state(LeaderBoardState::Hide))
+                        .run_if(in_state(ResumeState::Hide))
+                        .run_if(in_state(NewGameState::Hide)),
+                    handle_menu_events,
+                    render_menu_focus,
+                )
+                    .chain(),
+            );
     }
 }
 
-#[derive(Component)]
-enum MenuButton {
+#[derive(Component, Clone, Copy)]
+pub enum MenuButton {
     Restart,
+    Undo,
+    Redo,
+    Replay,
+    Hint,
     Leaderboard,
 }
 
+// footer buttons of the game, hidden while the replay controls are shown
+#[derive(Component)]
+pub struct MenuBar;
+
+// button pressed by the mouse, keyboard or gamepad
+#[derive(Event)]
+pub struct MenuEvent(pub MenuButton);
+
+// index of the footer button focused by the keyboard or gamepad
+#[derive(Resource, Default)]
+struct MenuFocus(usize);
+
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    undo_settings: Res<UndoSettings>,
+    hint_settings: Res<HintSettings>,
     q_footer: Query<Entity, With<Footer>>,
 ) {
     let font = asset_server.load("fonts/ThinPixel7.ttf");
@@ -52,27 +93,76 @@ fn setup(
                     width: Val::Percent(100.0),
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
+                    // buttons wrap in the portrait layout
+                    flex_wrap: FlexWrap::Wrap,
+                    row_gap: Val::Px(BUTTON_BORDER * 2.0),
                     ..default()
                 },
                 ..default()
             })
+            .insert(MenuBar)
             .with_children(|parent| {
-                spawn_button(parent, &text_style, "Restart", MenuButton::Restart);
-                spawn_button(parent, &text_style, "Leaderboard", MenuButton::Leaderboard);
+                spawn_button(
+                    parent,
+                    &text_style,
+                    BUTTON_WIDTH,
+                    "Restart",
+                    MenuButton::Restart,
+                );
+                if undo_settings.is_enabled() {
+                    spawn_button(
+                        parent,
+                        &text_style,
+                        SMALL_BUTTON_WIDTH,
+                        "Undo",
+                        MenuButton::Undo,
+                    );
+                    spawn_button(
+                        parent,
+                        &text_style,
+                        SMALL_BUTTON_WIDTH,
+                        "Redo",
+                        MenuButton::Redo,
+                    );
+                }
+                spawn_button(
+                    parent,
+                    &text_style,
+                    SMALL_BUTTON_WIDTH,
+                    "Replay",
+                    MenuButton::Replay,
+                );
+                if hint_settings.is_enabled() {
+                    spawn_button(
+                        parent,
+                        &text_style,
+                        SMALL_BUTTON_WIDTH,
+                        "Hint",
+                        MenuButton::Hint,
+                    );
+                }
+                spawn_button(
+                    parent,
+                    &text_style,
+                    BUTTON_WIDTH,
+                    "Leaderboard",
+                    MenuButton::Leaderboard,
+                );
             });
     });
 }
 
-fn spawn_button(
+pub fn spawn_button(
     parent: &mut ChildBuilder,
     text_style: &TextStyle,
+    width: f32,
     text: &str,
     comp: impl Component,
 ) {
     parent
         .spawn(ButtonBundle {
             style: Style {
-                width: Val::Px(BUTTON_WIDTH),
+                width: Val::Px(width),
                 height: Val::Px(BUTTON_HEIGHT),
                 border: UiRect::all(Val::Px(BUTTON_BORDER)),
                 // horizontally center child text
@@ -97,24 +187,13 @@ fn button_system(
         (&Interaction, &mut BackgroundColor, &MenuButton),
         (Changed<Interaction>, With<MenuButton>),
     >,
-    current_leaders_state: Res<State<LeaderBoardState>>,
-    mut next_game_state: ResMut<NextState<GameState>>,
-    mut next_leaders_state: ResMut<NextState<LeaderBoardState>>,
+    mut ev_menu: EventWriter<MenuEvent>,
 ) {
     for (interaction, mut color, button_type) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 *color = PRESSED_BUTTON.into();
-                match *button_type {
-                    MenuButton::Restart => {
-                        next_game_state.set(GameState::Restarting);
-                        next_leaders_state.set(LeaderBoardState::Hide);
-                    }
-                    MenuButton::Leaderboard => match current_leaders_state.get() {
-                        LeaderBoardState::Hide => next_leaders_state.set(LeaderBoardState::Show),
-                        _ => next_leaders_state.set(LeaderBoardState::Hide),
-                    },
-                }
+                ev_menu.send(MenuEvent(*button_type));
             }
             Interaction::Hovered => *color = HOVERED_BUTTON.into(),
             Interaction::None => *color = NORMAL_BUTTON.into(),
@@ -122,6 +201,110 @@ fn button_system(
     }
 }
 
+// tab or gamepad select moves the focus to the footer, arrows choose the button
+fn menu_keyboard_system(
+    actions: Actions,
+    focus: Res<State<InputFocus>>,
+    mut next_focus: ResMut<NextState<InputFocus>>,
+    mut menu_focus: ResMut<MenuFocus>,
+    q_menu: Query<&Children, With<MenuBar>>,
+    q_buttons: Query<&MenuButton>,
+    mut ev_menu: EventWriter<MenuEvent>,
+) {
+    if actions.just_pressed(Action::SwitchFocus) {
+        next_focus.set(match focus.get() {
+            InputFocus::Board => InputFocus::Menu,
+            InputFocus::Menu => InputFocus::Board,
+        });
+        return;
+    }
+    if *focus.get() != InputFocus::Menu {
+        return;
+    }
+
+    let buttons: Vec<MenuButton> = q_menu
+        .iter()
+        .flat_map(|children| children.iter())
+        .filter_map(|child| q_buttons.get(*child).ok())
+        .copied()
+        .collect();
+    if buttons.is_empty() {
+        return;
+    }
+
+    if actions.just_pressed(Action::Left) {
+        menu_focus.0 = (menu_focus.0 + buttons.len() - 1) % buttons.len();
+    }
+    if actions.just_pressed(Action::Right) {
+        menu_focus.0 = (menu_focus.0 + 1) % buttons.len();
+    }
+    if actions.just_pressed(Action::Confirm) {
+        ev_menu.send(MenuEvent(buttons[menu_focus.0 % buttons.len()]));
+    }
+    if actions.just_pressed(Action::Cancel) || actions.just_pressed(Action::Up) {
+        next_focus.set(InputFocus::Board);
+    }
+}
+
+pub fn handle_menu_events(
+    current_leaders_state: Res<State<LeaderBoardState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_leaders_state: ResMut<NextState<LeaderBoardState>>,
+    mut next_new_game_state: ResMut<NextState<NewGameState>>,
+    mut ev_history: EventWriter<HistoryEvent>,
+    mut ev_hint: EventWriter<HintEvent>,
+    mut ev_menu: EventReader<MenuEvent>,
+    last_replay: Res<LastReplay>,
+) {
+    for MenuEvent(button_type) in ev_menu.iter() {
+        match *button_type {
+            MenuButton::Restart => next_new_game_state.set(NewGameState::Show),
+            MenuButton::Undo => ev_history.send(HistoryEvent::Undo),
+            MenuButton::Redo => ev_history.send(HistoryEvent::Redo),
+            MenuButton::Hint => ev_hint.send(HintEvent),
+            MenuButton::Replay => {
+                if last_replay.0.is_some() {
+                    next_game_state.set(GameState::Replaying);
+                    next_leaders_state.set(LeaderBoardState::Hide);
+                }
+            }
+            MenuButton::Leaderboard => match current_leaders_state.get() {
+                LeaderBoardState::Hide => next_leaders_state.set(LeaderBoardState::Show),
+                _ => next_leaders_state.set(LeaderBoardState::Hide),
+            },
+        }
+    }
+}
+
+// border of the focused footer button
+fn render_menu_focus(
+    focus: Res<State<InputFocus>>,
+    menu_focus: Res<MenuFocus>,
+    q_menu: Query<&Children, With<MenuBar>>,
+    mut q_buttons: Query<&mut BorderColor, With<MenuButton>>,
+) {
+    if !focus.is_changed() && !menu_focus.is_changed() {
+        return;
+    }
+    let buttons: Vec<Entity> = q_menu
+        .iter()
+        .flat_map(|children| children.iter())
+        .filter(|child| q_buttons.contains(**child))
+        .copied()
+        .collect();
+
+    for (index, button) in buttons.iter().enumerate() {
+        if let Ok(mut border) = q_buttons.get_mut(*button) {
+            border.0 = if *focus.get() == InputFocus::Menu && index == menu_focus.0 % buttons.len()
+            {
+                FOCUSED_BORDER_COLOR
+            } else {
+                BUTTON_BORDER_COLOR
+            };
+        }
+    }
+}
+
 fn start_game(mut game_state: ResMut<NextState<GameState>>) {
     game_state.set(GameState::Playing);
 }
@@ -1,5 +1,11 @@
+pub mod autoplay;
 pub mod board;
 pub mod game_score;
 pub mod layout;
 pub mod leader_board;
+pub mod leader_board_file;
 pub mod menu;
+pub mod new_game;
+pub mod replay;
+pub mod save;
+pub mod seed;
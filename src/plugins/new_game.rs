@@ -0,0 +1,369 @@
+use bevy::prelude::*;
+use lines_core::{BallColor, GameConfig, Preset, ScoringRule};
+use std::ops::RangeInclusive;
+
+use super::layout::MainCenter;
+use super::menu::{
+    spawn_button, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON, SMALL_BUTTON_WIDTH,
+};
+use crate::args;
+use crate::GameOptions;
+use crate::GameState;
+use crate::LeaderBoardState;
+use crate::NewGameState;
+
+pub struct NewGamePlugin;
+
+impl Plugin for NewGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(config_from_args())
+            .init_resource::<DraftConfig>()
+            .add_systems(OnEnter(NewGameState::Show), spawn_new_game_dialog)
+            .add_systems(OnExit(NewGameState::Show), despawn_new_game_dialog)
+            .add_systems(
+                Update,
+                (new_game_button_system, render_draft_config)
+                    .chain()
+                    .run_if(in_state(NewGameState::Show)),
+            );
+    }
+}
+
+// `--preset hard` and `--scoring combo` choose the rules of the first game, like in lines-sim
+pub fn config_from_args() -> GameConfig {
+    let preset = args::get("preset")
+        .and_then(|name| {
+            Preset::ALL
+                .into_iter()
+                .find(|preset| preset.name().eq_ignore_ascii_case(&name))
+        })
+        .unwrap_or(Preset::Classic);
+    let scoring = args::get("scoring").and_then(|name| {
+        ScoringRule::ALL
+            .into_iter()
+            .find(|rule| rule.name().eq_ignore_ascii_case(&name))
+    });
+
+    GameConfig {
+        scoring: scoring.unwrap_or(preset.config().scoring),
+        ..preset.config()
+    }
+}
+
+// config edited in the dialog, applied to the next game by the start button
+#[derive(Resource, Default)]
+struct DraftConfig(GameConfig);
+
+#[derive(Clone, Copy, PartialEq)]
+enum ConfigField {
+    Width,
+    Height,
+    Colors,
+    LineLength,
+    BallsPerTurn,
+    StartingBalls,
+    Scoring,
+}
+
+impl ConfigField {
+    const ALL: [ConfigField; 7] = [
+        ConfigField::Width,
+        ConfigField::Height,
+        ConfigField::Colors,
+        ConfigField::LineLength,
+        ConfigField::BallsPerTurn,
+        ConfigField::StartingBalls,
+        ConfigField::Scoring,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ConfigField::Width => "Width",
+            ConfigField::Height => "Height",
+            ConfigField::Colors => "Colors",
+            ConfigField::LineLength => "Line length",
+            ConfigField::BallsPerTurn => "Balls per turn",
+            ConfigField::StartingBalls => "Starting balls",
+            ConfigField::Scoring => "Scoring",
+        }
+    }
+
+    fn range(&self) -> RangeInclusive<usize> {
+        let max_side = GameOptions::MAX_TILE_COUNT as usize;
+        match self {
+            ConfigField::Width | ConfigField::Height => 5..=max_side,
+            ConfigField::Colors => 2..=BallColor::ALL.len(),
+            ConfigField::LineLength => 3..=max_side,
+            ConfigField::BallsPerTurn => 1..=5,
+            ConfigField::StartingBalls => 1..=10,
+            // index of the scoring rule
+            ConfigField::Scoring => 0..=ScoringRule::ALL.len() - 1,
+        }
+    }
+
+    fn get(&self, config: &GameConfig) -> usize {
+        match self {
+            ConfigField::Width => config.width as usize,
+            ConfigField::Height => config.height as usize,
+            ConfigField::Colors => config.colors as usize,
+            ConfigField::LineLength => config.line_length,
+            ConfigField::BallsPerTurn => config.balls_per_turn,
+            ConfigField::StartingBalls => config.starting_balls,
+            ConfigField::Scoring => ScoringRule::ALL
+                .iter()
+                .position(|rule| *rule == config.scoring)
+                .unwrap_or(0),
+        }
+    }
+
+    fn set(&self, config: &mut GameConfig, value: usize) {
+        match self {
+            ConfigField::Width => config.width = value as u8,
+            ConfigField::Height => config.height = value as u8,
+            ConfigField::Colors => config.colors = value as u8,
+            ConfigField::LineLength => config.line_length = value,
+            ConfigField::BallsPerTurn => config.balls_per_turn = value,
+            ConfigField::StartingBalls => config.starting_balls = value,
+            ConfigField::Scoring => config.scoring = ScoringRule::ALL[value],
+        }
+    }
+
+    fn display(&self, config: &GameConfig) -> String {
+        match self {
+            ConfigField::Scoring => config.scoring.name().to_string(),
+            _ => self.get(config).to_string(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct NewGameDialog;
+
+#[derive(Component)]
+enum NewGameButton {
+    Preset(Preset),
+    Decrease(ConfigField),
+    Increase(ConfigField),
+    Start,
+    Cancel,
+}
+
+// text with the value of the config field
+#[derive(Component)]
+struct ConfigValue(ConfigField);
+
+// preset of the draft config or the reason why the game can't be started with it
+#[derive(Component)]
+struct ConfigStatusText;
+
+fn spawn_new_game_dialog(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    mut draft: ResMut<DraftConfig>,
+    q_main: Query<Entity, With<MainCenter>>,
+) {
+    draft.0 = *config;
+
+    let font = asset_server.load("fonts/ThinPixel7.ttf");
+    let text_style = TextStyle {
+        font,
+        font_size: 35.0,
+        color: Color::DARK_GRAY,
+    };
+
+    let main = q_main.get_single().expect("Main not found");
+
+    commands.entity(main).with_children(|main| {
+        main.spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(GameOptions::TILE_PADDING)),
+                padding: UiRect::all(Val::Px(GameOptions::TILE_PADDING * 4.0)),
+                ..default()
+            },
+            border_color: GameOptions::BOARD_COLOR.into(),
+            background_color: GameOptions::TILE_COLOR.into(),
+            z_index: ZIndex::Global(100),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::bottom(Val::Px(GameOptions::TILE_PADDING * 4.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for preset in Preset::ALL {
+                        spawn_button(
+                            parent,
+                            &text_style,
+                            SMALL_BUTTON_WIDTH * 0.8,
+                            preset.name(),
+                            NewGameButton::Preset(preset),
+                        );
+                    }
+                });
+
+            for field in ConfigField::ALL {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(
+                            TextBundle::from_section(field.label(), text_style.clone()).with_style(
+                                Style {
+                                    width: Val::Px(SMALL_BUTTON_WIDTH * 1.5),
+                                    ..default()
+                                },
+                            ),
+                        );
+                        spawn_button(
+                            parent,
+                            &text_style,
+                            GameOptions::TILE_SIZE,
+                            "-",
+                            NewGameButton::Decrease(field),
+                        );
+                        parent.spawn((
+                            TextBundle::from_section("", text_style.clone())
+                                .with_text_alignment(TextAlignment::Center)
+                                .with_style(Style {
+                                    width: Val::Px(SMALL_BUTTON_WIDTH),
+                                    justify_content: JustifyContent::Center,
+                                    ..default()
+                                }),
+                            ConfigValue(field),
+                        ));
+                        spawn_button(
+                            parent,
+                            &text_style,
+                            GameOptions::TILE_SIZE,
+                            "+",
+                            NewGameButton::Increase(field),
+                        );
+                    });
+            }
+
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 25.0,
+                        color: GameOptions::BOARD_COLOR,
+                        ..text_style.clone()
+                    },
+                ),
+                ConfigStatusText,
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(GameOptions::TILE_PADDING * 4.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(
+                        parent,
+                        &text_style,
+                        SMALL_BUTTON_WIDTH * 1.5,
+                        "Start",
+                        NewGameButton::Start,
+                    );
+                    spawn_button(
+                        parent,
+                        &text_style,
+                        SMALL_BUTTON_WIDTH * 1.5,
+                        "Cancel",
+                        NewGameButton::Cancel,
+                    );
+                });
+        })
+        .insert(NewGameDialog);
+    });
+}
+
+fn despawn_new_game_dialog(mut commands: Commands, q_dialog: Query<Entity, With<NewGameDialog>>) {
+    for entity in q_dialog.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn new_game_button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &NewGameButton),
+        Changed<Interaction>,
+    >,
+    mut config: ResMut<GameConfig>,
+    mut draft: ResMut<DraftConfig>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_leaders_state: ResMut<NextState<LeaderBoardState>>,
+    mut next_new_game_state: ResMut<NextState<NewGameState>>,
+) {
+    for (interaction, mut color, button_type) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                match *button_type {
+                    NewGameButton::Preset(preset) => draft.0 = preset.config(),
+                    NewGameButton::Decrease(field) => {
+                        let value = field.get(&draft.0).saturating_sub(1);
+                        if field.range().contains(&value) {
+                            field.set(&mut draft.0, value);
+                        }
+                    }
+                    NewGameButton::Increase(field) => {
+                        let value = field.get(&draft.0) + 1;
+                        if field.range().contains(&value) {
+                            field.set(&mut draft.0, value);
+                        }
+                    }
+                    NewGameButton::Start => {
+                        if draft.0.validate().is_ok() {
+                            *config = draft.0;
+                            next_game_state.set(GameState::Restarting);
+                            next_leaders_state.set(LeaderBoardState::Hide);
+                            next_new_game_state.set(NewGameState::Hide);
+                        }
+                    }
+                    NewGameButton::Cancel => next_new_game_state.set(NewGameState::Hide),
+                }
+            }
+            Interaction::Hovered => *color = HOVERED_BUTTON.into(),
+            Interaction::None => *color = NORMAL_BUTTON.into(),
+        }
+    }
+}
+
+fn render_draft_config(
+    draft: Res<DraftConfig>,
+    mut q_values: Query<(&ConfigValue, &mut Text), Without<ConfigStatusText>>,
+    mut q_status: Query<&mut Text, With<ConfigStatusText>>,
+) {
+    if !draft.is_changed() {
+        return;
+    }
+    for (value, mut text) in q_values.iter_mut() {
+        text.sections[0].value = value.0.display(&draft.0);
+    }
+    for mut text in q_status.iter_mut() {
+        text.sections[0].value = match (draft.0.validate(), Preset::from_config(&draft.0)) {
+            (Err(error), _) => error.to_string(),
+            (Ok(()), Some(preset)) => preset.name().to_string(),
+            (Ok(()), None) => "Custom rules, not ranked".to_string(),
+        };
+    }
+}
@@ -0,0 +1,500 @@
+use bevy::prelude::*;
+use lines_core::Replay;
+
+use super::autoplay::Autoplay;
+use super::board::board::Board;
+use super::board::{BoardEvent, RefreshBoardEvent};
+use super::layout::Footer;
+use super::leader_board::PlayTime;
+use super::menu::{
+    spawn_button, MenuBar, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON, SMALL_BUTTON_WIDTH,
+};
+use super::save::SavedGame;
+use crate::args;
+use crate::storage;
+use crate::GameState;
+
+const LAST_REPLAY_KEY: &str = "last_replay";
+
+// moves per second
+const SPEEDS: [f32; 4] = [1.0, 2.0, 4.0, 8.0];
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LastReplay(load_replay()))
+            .init_resource::<ReplayPlayer>()
+            .add_systems(Startup, start_replay_from_args)
+            .add_systems(OnEnter(GameState::GameOver), save_replay)
+            .add_systems(
+                OnEnter(GameState::Replaying),
+                ((stash_game, start_replay).chain(), spawn_replay_controls),
+            )
+            .add_systems(OnExit(GameState::Replaying), despawn_replay_controls)
+            .add_systems(
+                Update,
+                (
+                    replay_button_system,
+                    replay_keyboard_system,
+                    replay_autoplay_system,
+                    render_replay_controls,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Replaying)),
+            );
+    }
+}
+
+// replay of the last finished game or of the file from `--replay path`
+#[derive(Resource, Default)]
+pub struct LastReplay(pub Option<Replay>);
+
+#[derive(Resource)]
+struct ReplayPlayer {
+    replay: Option<Replay>,
+    // count of played moves
+    step: usize,
+    playing: bool,
+    speed: usize,
+    timer: Timer,
+}
+
+impl Default for ReplayPlayer {
+    fn default() -> Self {
+        Self {
+            replay: None,
+            step: 0,
+            playing: false,
+            speed: 0,
+            timer: Timer::from_seconds(1.0 / SPEEDS[0], TimerMode::Repeating),
+        }
+    }
+}
+
+impl ReplayPlayer {
+    fn step_forward(&mut self, board: &mut Board) -> Option<Vec<BoardEvent>> {
+        let (from, to) = *self.replay.as_ref()?.moves.get(self.step)?;
+        match board.game.make_move(from, to) {
+            Ok(events) => {
+                self.step += 1;
+                Some(events.into_iter().map(BoardEvent).collect())
+            }
+            Err(error) => {
+                warn!("Replay stopped at move {}: {error}", self.step);
+                self.playing = false;
+                None
+            }
+        }
+    }
+
+    fn step_back(&mut self, board: &mut Board) -> bool {
+        if board.game.undo().is_some() {
+            self.step -= 1;
+            return true;
+        }
+        false
+    }
+
+    fn is_finished(&self) -> bool {
+        self.replay
+            .as_ref()
+            .is_none_or(|replay| self.step >= replay.moves.len())
+    }
+
+    fn toggle_speed(&mut self) {
+        self.speed = (self.speed + 1) % SPEEDS.len();
+        self.timer = Timer::from_seconds(1.0 / SPEEDS[self.speed], TimerMode::Repeating);
+    }
+}
+
+#[derive(Component)]
+enum ReplayButton {
+    Back,
+    PlayPause,
+    Forward,
+    Speed,
+    Exit,
+}
+
+#[derive(Component)]
+struct ReplayControls;
+
+fn load_replay() -> Option<Replay> {
+    let data = match args::get("replay") {
+        #[cfg(not(target_arch = "wasm32"))]
+        Some(path) if !path.is_empty() => std::fs::read_to_string(path).ok()?,
+        _ => storage::read(LAST_REPLAY_KEY)?,
+    };
+    serde_json::from_str(&data).ok()
+}
+
+// `--replay path` on desktop or `?replay` in the page url opens the replay viewer on start
+fn start_replay_from_args(
+    last_replay: Res<LastReplay>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    if args::has("replay") && last_replay.0.is_some() {
+        game_state.set(GameState::Replaying);
+    }
+}
+
+fn save_replay(board: Res<Board>, mut last_replay: ResMut<LastReplay>) {
+    let replay = Replay::from_game(&board.game);
+
+    if let Ok(data) = serde_json::to_string(&replay) {
+        storage::write(LAST_REPLAY_KEY, &data);
+
+        // keep replays of all games on desktop
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(time) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            let key = format!("replays/{}-{}", time.as_secs(), replay.score);
+            storage::write(&key, &data);
+        }
+    }
+
+    last_replay.0 = Some(replay);
+}
+
+// the unfinished game goes on after the replay, the board takes it back as the saved game
+fn stash_game(
+    board: Res<Board>,
+    autoplay: Res<Autoplay>,
+    play_time: Res<PlayTime>,
+    mut saved_game: ResMut<SavedGame>,
+) {
+    if board.game.is_over() || autoplay.played {
+        return;
+    }
+    *saved_game = SavedGame {
+        game: Some(board.game.clone()),
+        play_time: Some(play_time.0.elapsed()),
+    };
+}
+
+fn start_replay(
+    last_replay: Res<LastReplay>,
+    mut player: ResMut<ReplayPlayer>,
+    mut board: ResMut<Board>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut ev_refresh: EventWriter<RefreshBoardEvent>,
+) {
+    let game = last_replay.0.as_ref().map(|replay| replay.start_game());
+
+    match game {
+        Some(Ok(game)) => {
+            board.game = game;
+            board.active_ball = None;
+            ev_refresh.send(RefreshBoardEvent);
+
+            *player = ReplayPlayer {
+                replay: last_replay.0.clone(),
+                playing: true,
+                ..default()
+            };
+        }
+        Some(Err(error)) => {
+            warn!("Can't play replay: {error}");
+            game_state.set(GameState::Restarting);
+        }
+        None => game_state.set(GameState::Restarting),
+    }
+}
+
+fn spawn_replay_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    last_replay: Res<LastReplay>,
+    q_footer: Query<Entity, With<Footer>>,
+    mut q_menu: Query<&mut Style, With<MenuBar>>,
+) {
+    let font = asset_server.load("fonts/ThinPixel7.ttf");
+    let text_style = TextStyle {
+        font,
+        font_size: 35.0,
+        color: Color::DARK_GRAY,
+    };
+
+    for mut style in q_menu.iter_mut() {
+        style.display = Display::None;
+    }
+
+    let footer = q_footer.get_single().expect("Footer not found");
+
+    commands.entity(footer).with_children(|footer| {
+        footer
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(ReplayControls)
+            .with_children(|parent| {
+                spawn_button(
+                    parent,
+                    &text_style,
+                    SMALL_BUTTON_WIDTH,
+                    "<",
+                    ReplayButton::Back,
+                );
+                spawn_button(
+                    parent,
+                    &text_style,
+                    SMALL_BUTTON_WIDTH,
+                    "",
+                    ReplayButton::PlayPause,
+                );
+                spawn_button(
+                    parent,
+                    &text_style,
+                    SMALL_BUTTON_WIDTH,
+                    ">",
+                    ReplayButton::Forward,
+                );
+                spawn_button(
+                    parent,
+                    &text_style,
+                    SMALL_BUTTON_WIDTH,
+                    "",
+                    ReplayButton::Speed,
+                );
+                spawn_button(
+                    parent,
+                    &text_style,
+                    SMALL_BUTTON_WIDTH,
+                    "Exit",
+                    ReplayButton::Exit,
+                );
+                // the game was played with hints
+                if let Some(replay) = last_replay.0.as_ref().filter(|replay| replay.is_hinted()) {
+                    parent.spawn(TextBundle::from_section(
+                        format!("Hints: {}", replay.hints),
+                        text_style.clone(),
+                    ));
+                }
+            });
+    });
+}
+
+fn despawn_replay_controls(
+    mut commands: Commands,
+    q_controls: Query<Entity, With<ReplayControls>>,
+    mut q_menu: Query<&mut Style, With<MenuBar>>,
+) {
+    for entity in q_controls.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for mut style in q_menu.iter_mut() {
+        style.display = Display::Flex;
+    }
+}
+
+fn replay_button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &ReplayButton),
+        Changed<Interaction>,
+    >,
+    mut player: ResMut<ReplayPlayer>,
+    mut board: ResMut<Board>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut ev_board: EventWriter<BoardEvent>,
+    mut ev_refresh: EventWriter<RefreshBoardEvent>,
+) {
+    for (interaction, mut color, button_type) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                match *button_type {
+                    ReplayButton::Back => {
+                        player.playing = false;
+                        if player.step_back(&mut board) {
+                            ev_refresh.send(RefreshBoardEvent);
+                        }
+                    }
+                    ReplayButton::PlayPause => player.playing = !player.playing,
+                    ReplayButton::Forward => {
+                        player.playing = false;
+                        if let Some(events) = player.step_forward(&mut board) {
+                            ev_board.send_batch(events);
+                        }
+                    }
+                    ReplayButton::Speed => player.toggle_speed(),
+                    ReplayButton::Exit => game_state.set(GameState::Restarting),
+                }
+            }
+            Interaction::Hovered => *color = HOVERED_BUTTON.into(),
+            Interaction::None => *color = NORMAL_BUTTON.into(),
+        }
+    }
+}
+
+// space to play or pause, arrows to step back and forward, escape to exit
+fn replay_keyboard_system(
+    keys: Res<Input<KeyCode>>,
+    mut player: ResMut<ReplayPlayer>,
+    mut board: ResMut<Board>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut ev_board: EventWriter<BoardEvent>,
+    mut ev_refresh: EventWriter<RefreshBoardEvent>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        player.playing = !player.playing;
+    }
+    if keys.just_pressed(KeyCode::Left) {
+        player.playing = false;
+        if player.step_back(&mut board) {
+            ev_refresh.send(RefreshBoardEvent);
+        }
+    }
+    if keys.just_pressed(KeyCode::Right) {
+        player.playing = false;
+        if let Some(events) = player.step_forward(&mut board) {
+            ev_board.send_batch(events);
+        }
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        game_state.set(GameState::Restarting);
+    }
+}
+
+fn replay_autoplay_system(
+    time: Res<Time>,
+    mut player: ResMut<ReplayPlayer>,
+    mut board: ResMut<Board>,
+    mut ev_board: EventWriter<BoardEvent>,
+) {
+    if !player.playing {
+        return;
+    }
+    if player.is_finished() {
+        player.playing = false;
+        return;
+    }
+    if player.timer.tick(time.delta()).just_finished() {
+        if let Some(events) = player.step_forward(&mut board) {
+            ev_board.send_batch(events);
+        }
+    }
+}
+
+fn render_replay_controls(
+    player: Res<ReplayPlayer>,
+    q_buttons: Query<(&ReplayButton, &Children)>,
+    mut q_text: Query<&mut Text>,
+) {
+    if !player.is_changed() {
+        return;
+    }
+    for (button_type, children) in q_buttons.iter() {
+        let value = match button_type {
+            ReplayButton::PlayPause if player.playing => "Pause".to_string(),
+            ReplayButton::PlayPause => "Play".to_string(),
+            ReplayButton::Speed => format!("x{}", SPEEDS[player.speed]),
+            _ => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(*child) {
+                text.sections[0].value = value.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::board::{
+        spawn_startup_balls, HintEvent, HintSettings, HistoryEvent, UndoSettings,
+    };
+    use crate::plugins::menu::{handle_menu_events, MenuButton, MenuEvent};
+    use crate::plugins::seed::GameSeed;
+    use crate::{LeaderBoardState, NewGameState};
+    use lines_core::{Game, GameConfig, Random, Strategy};
+
+    fn play(game: &mut Game, player: &mut Random, moves: usize) {
+        for _ in 0..moves {
+            if let Some((from, to)) = player.choose_move(game) {
+                game.make_move(from, to).unwrap();
+            }
+        }
+    }
+
+    // menu, replay and board systems switching the games, without the ui
+    fn app() -> App {
+        let mut replayed = Game::new(1);
+        replayed.start();
+        play(&mut replayed, &mut Random::new(1), 1);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<GameState>()
+            .add_state::<LeaderBoardState>()
+            .add_state::<NewGameState>()
+            .add_event::<MenuEvent>()
+            .add_event::<HistoryEvent>()
+            .add_event::<HintEvent>()
+            .add_event::<RefreshBoardEvent>()
+            .init_resource::<Board>()
+            .init_resource::<ReplayPlayer>()
+            .init_resource::<SavedGame>()
+            .init_resource::<GameSeed>()
+            .init_resource::<GameConfig>()
+            .init_resource::<UndoSettings>()
+            .init_resource::<HintSettings>()
+            .init_resource::<Autoplay>()
+            .init_resource::<PlayTime>()
+            .insert_resource(LastReplay(Some(Replay::from_game(&replayed))))
+            .add_systems(Update, handle_menu_events)
+            .add_systems(
+                OnEnter(GameState::Replaying),
+                (stash_game, start_replay).chain(),
+            )
+            .add_systems(
+                OnEnter(GameState::Restarting),
+                |mut state: ResMut<NextState<GameState>>| state.set(GameState::Playing),
+            )
+            .add_systems(OnEnter(GameState::Playing), spawn_startup_balls);
+        app.update();
+        app
+    }
+
+    fn state(app: &App) -> &GameState {
+        app.world.resource::<State<GameState>>().get()
+    }
+
+    #[test]
+    fn replay_keeps_the_unfinished_game() {
+        let mut app = app();
+        play(
+            &mut app.world.resource_mut::<Board>().game,
+            &mut Random::new(7),
+            3,
+        );
+        let game = app.world.resource::<Board>().game.clone();
+
+        app.world.send_event(MenuEvent(MenuButton::Replay));
+        app.update();
+        app.update();
+        assert_eq!(state(&app), &GameState::Replaying);
+        assert_ne!(app.world.resource::<Board>().game.seed(), game.seed());
+
+        // the exit button of the replay controls
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Restarting);
+        app.update();
+        app.update();
+        assert_eq!(state(&app), &GameState::Playing);
+
+        let resumed = &app.world.resource::<Board>().game;
+        assert_eq!(resumed.seed(), game.seed());
+        assert_eq!(resumed.board(), game.board());
+        assert_eq!(resumed.next_balls(), game.next_balls());
+        assert_eq!(resumed.score(), game.score());
+        assert_eq!(resumed.history(), game.history());
+    }
+}
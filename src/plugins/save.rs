@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use lines_core::Game;
+use serde::{Deserialize, Serialize};
+
+use super::autoplay::Autoplay;
+use super::board::board::Board;
+use super::board::{BoardEvent, RefreshBoardEvent};
+use super::layout::MainCenter;
+use super::leader_board::PlayTime;
+use super::menu::{
+    spawn_button, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON, SMALL_BUTTON_WIDTH,
+};
+use crate::storage;
+use crate::GameOptions;
+use crate::GameState;
+use crate::ResumeState;
+
+// increment on incompatible changes of the saved game format
+const SAVE_VERSION: u32 = 3;
+const SAVE_KEY: &str = "saved_game";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_game())
+            .add_systems(Startup, show_resume_dialog)
+            .add_systems(OnEnter(ResumeState::Show), spawn_resume_dialog)
+            .add_systems(OnEnter(ResumeState::Hide), despawn_resume_dialog)
+            .add_systems(OnEnter(GameState::GameOver), remove_saved_game)
+            .add_systems(
+                Update,
+                (
+                    save_game.run_if(in_state(GameState::Playing)),
+                    resume_button_system,
+                ),
+            );
+    }
+}
+
+// game from the last session, taken by the board on start
+#[derive(Resource, Default)]
+pub struct SavedGame {
+    pub game: Option<Game>,
+    // time spent in the game, taken by the play time of the leader board
+    pub play_time: Option<Duration>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    game: Game,
+    #[serde(default)]
+    play_time: Duration,
+}
+
+#[derive(Component)]
+struct ResumeDialog;
+
+#[derive(Component)]
+enum ResumeButton {
+    Continue,
+    NewGame,
+}
+
+fn load_game() -> SavedGame {
+    let Some(data) = storage::read(SAVE_KEY) else {
+        return SavedGame::default();
+    };
+    let game = match serde_json::from_str::<SaveFile>(&data) {
+        Ok(save) if save.version != SAVE_VERSION || save.game.is_over() => None,
+        Ok(save) if save.game.is_valid() => Some(save),
+        // the game doesn't fit its rules and would break on the first move
+        Ok(_) => {
+            warn!("Saved game is corrupt: the board doesn't match the rules");
+            storage::quarantine(SAVE_KEY);
+            None
+        }
+        // games of the other versions don't match the file either, only broken json is kept aside
+        Err(error) if error.is_syntax() || error.is_eof() => {
+            warn!("Saved game is corrupt: {error}");
+            storage::quarantine(SAVE_KEY);
+            None
+        }
+        Err(_) => None,
+    };
+    SavedGame {
+        play_time: game.as_ref().map(|save| save.play_time),
+        game: game.map(|save| save.game),
+    }
+}
+
+fn save_game(
+    board: Res<Board>,
+    autoplay: Res<Autoplay>,
+    play_time: Res<PlayTime>,
+    mut ev_board: EventReader<BoardEvent>,
+    mut ev_refresh: EventReader<RefreshBoardEvent>,
+) {
+    // save after every change of the game rules state
+    let changed = ev_board.iter().count() + ev_refresh.iter().count() > 0;
+    if !changed || board.game.is_over() || autoplay.played {
+        return;
+    }
+
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        game: board.game.clone(),
+        play_time: play_time.0.elapsed(),
+    };
+    if let Ok(data) = serde_json::to_string(&save) {
+        storage::write(SAVE_KEY, &data);
+    }
+}
+
+fn remove_saved_game() {
+    storage::remove(SAVE_KEY);
+}
+
+fn show_resume_dialog(saved_game: Res<SavedGame>, mut state: ResMut<NextState<ResumeState>>) {
+    if saved_game.game.is_some() {
+        state.set(ResumeState::Show);
+    }
+}
+
+fn spawn_resume_dialog(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    q_main: Query<Entity, With<MainCenter>>,
+) {
+    let font = asset_server.load("fonts/ThinPixel7.ttf");
+    let text_style = TextStyle {
+        font,
+        font_size: 35.0,
+        color: Color::DARK_GRAY,
+    };
+
+    let main = q_main.get_single().expect("Main not found");
+
+    commands.entity(main).with_children(|main| {
+        main.spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(GameOptions::TILE_PADDING)),
+                padding: UiRect::all(Val::Px(GameOptions::TILE_PADDING * 4.0)),
+                ..default()
+            },
+            border_color: GameOptions::BOARD_COLOR.into(),
+            background_color: GameOptions::TILE_COLOR.into(),
+            z_index: ZIndex::Global(100),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Continue the last game?",
+                text_style.clone(),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(GameOptions::TILE_PADDING * 4.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(
+                        parent,
+                        &text_style,
+                        SMALL_BUTTON_WIDTH * 1.5,
+                        "Continue",
+                        ResumeButton::Continue,
+                    );
+                    spawn_button(
+                        parent,
+                        &text_style,
+                        SMALL_BUTTON_WIDTH * 1.5,
+                        "New game",
+                        ResumeButton::NewGame,
+                    );
+                });
+        })
+        .insert(ResumeDialog);
+    });
+}
+
+fn despawn_resume_dialog(mut commands: Commands, q_dialog: Query<Entity, With<ResumeDialog>>) {
+    for entity in q_dialog.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn resume_button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &ResumeButton),
+        Changed<Interaction>,
+    >,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_resume_state: ResMut<NextState<ResumeState>>,
+) {
+    for (interaction, mut color, button_type) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = PRESSED_BUTTON.into();
+                if let ResumeButton::NewGame = button_type {
+                    next_game_state.set(GameState::Restarting);
+                }
+                next_resume_state.set(ResumeState::Hide);
+            }
+            Interaction::Hovered => *color = HOVERED_BUTTON.into(),
+            Interaction::None => *color = NORMAL_BUTTON.into(),
+        }
+    }
+}
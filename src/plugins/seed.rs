@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use super::board::board::Board;
+use super::layout::MainCenter;
+use crate::args;
+use crate::GameState;
+
+pub struct SeedPlugin;
+
+impl Plugin for SeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameSeed(args::parse("seed")))
+            .add_systems(OnEnter(GameState::GameOver), spawn_seed_text)
+            .add_systems(OnExit(GameState::GameOver), despawn_seed_text);
+    }
+}
+
+// seed for the next game, random if not set
+#[derive(Resource, Default)]
+pub struct GameSeed(pub Option<u64>);
+
+impl GameSeed {
+    pub fn take(&mut self) -> u64 {
+        self.0.take().unwrap_or_else(rand::random)
+    }
+}
+
+#[derive(Component)]
+struct SeedText;
+
+fn spawn_seed_text(
+    mut commands: Commands,
+    board: Res<Board>,
+    asset_server: Res<AssetServer>,
+    q_main: Query<Entity, With<MainCenter>>,
+) {
+    let font = asset_server.load("fonts/ThinPixel7.ttf");
+    let text_style = TextStyle {
+        font,
+        font_size: 35.0,
+        color: Color::YELLOW_GREEN,
+    };
+
+    let main = q_main.get_single().expect("Main not found");
+
+    commands.entity(main).with_children(|main| {
+        main.spawn((
+            TextBundle {
+                text: Text::from_section(format!("Seed: {}", board.game.seed()), text_style),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(0.0),
+                    ..default()
+                },
+                ..default()
+            },
+            SeedText,
+        ));
+    });
+}
+
+fn despawn_seed_text(mut commands: Commands, q_seed_text: Query<Entity, With<SeedText>>) {
+    for entity in q_seed_text.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,169 @@
+// key-value storage for game data: json files in the user data directory on desktop,
+// e.g. `~/.local/share/bevy_lines/saved_game.json`, and local storage in the browser.
+// `--data-dir path` keeps the files of the desktop game in another directory
+
+pub trait Storage {
+    fn read(&self, key: &str) -> Option<String>;
+
+    fn write(&self, key: &str, data: &str) -> Option<()>;
+
+    fn remove(&self, key: &str) -> Option<()>;
+
+    // move unreadable data aside, so it isn't overwritten and the key can be used again
+    fn quarantine(&self, key: &str) -> Option<()> {
+        let data = self.read(key)?;
+        self.write(&format!("{key}.corrupt"), &data)?;
+        self.remove(key)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use super::Storage;
+    use crate::args;
+
+    pub struct FileStorage {
+        dir: PathBuf,
+    }
+
+    impl FileStorage {
+        pub fn new(dir: PathBuf) -> Self {
+            Self { dir }
+        }
+
+        fn path(&self, key: &str) -> PathBuf {
+            self.dir.join(format!("{key}.json"))
+        }
+    }
+
+    impl Storage for FileStorage {
+        fn read(&self, key: &str) -> Option<String> {
+            fs::read_to_string(self.path(key)).ok()
+        }
+
+        // the data is written to the temporary file first, so the old file is replaced only
+        // by the complete new one
+        fn write(&self, key: &str, data: &str) -> Option<()> {
+            let path = self.path(key);
+            fs::create_dir_all(path.parent()?).ok()?;
+
+            let tmp_path = path.with_extension("json.tmp");
+            let mut file = fs::File::create(&tmp_path).ok()?;
+            file.write_all(data.as_bytes()).ok()?;
+            file.sync_all().ok()?;
+            fs::rename(tmp_path, path).ok()
+        }
+
+        fn remove(&self, key: &str) -> Option<()> {
+            fs::remove_file(self.path(key)).ok()
+        }
+
+        fn quarantine(&self, key: &str) -> Option<()> {
+            let path = self.path(key);
+            fs::rename(&path, path.with_extension("corrupt.json")).ok()
+        }
+    }
+
+    pub fn backend() -> Option<FileStorage> {
+        let dir = match args::get("data-dir") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => dirs::data_dir()?.join("bevy_lines"),
+        };
+        Some(FileStorage::new(dir))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use super::Storage;
+
+    pub struct LocalStorage(web_sys::Storage);
+
+    impl Storage for LocalStorage {
+        fn read(&self, key: &str) -> Option<String> {
+            self.0.get_item(key).ok()?
+        }
+
+        fn write(&self, key: &str, data: &str) -> Option<()> {
+            self.0.set_item(key, data).ok()
+        }
+
+        fn remove(&self, key: &str) -> Option<()> {
+            self.0.remove_item(key).ok()
+        }
+    }
+
+    pub fn backend() -> Option<LocalStorage> {
+        Some(LocalStorage(web_sys::window()?.local_storage().ok()??))
+    }
+}
+
+pub use platform::backend;
+
+pub fn read(key: &str) -> Option<String> {
+    backend()?.read(key)
+}
+
+pub fn write(key: &str, data: &str) -> Option<()> {
+    backend()?.write(key, data)
+}
+
+pub fn remove(key: &str) -> Option<()> {
+    backend()?.remove(key)
+}
+
+pub fn quarantine(key: &str) -> Option<()> {
+    backend()?.quarantine(key)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::platform::FileStorage;
+    use super::Storage;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bevy_lines_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn write_read_and_remove() {
+        let dir = temp_dir("storage");
+        let storage = FileStorage::new(dir.clone());
+
+        assert_eq!(storage.read("key"), None);
+        storage.write("key", "first").unwrap();
+        storage.write("key", "second").unwrap();
+        assert_eq!(storage.read("key").as_deref(), Some("second"));
+        // no temporary files are left
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        storage.write("nested/key", "data").unwrap();
+        assert_eq!(storage.read("nested/key").as_deref(), Some("data"));
+
+        storage.remove("key").unwrap();
+        assert_eq!(storage.read("key"), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn quarantine_keeps_corrupt_data_aside() {
+        let dir = temp_dir("quarantine");
+        let storage = FileStorage::new(dir.clone());
+
+        storage.write("key", "{broken").unwrap();
+        storage.quarantine("key").unwrap();
+        assert_eq!(storage.read("key"), None);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("key.corrupt.json")).unwrap(),
+            "{broken"
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}